@@ -0,0 +1,297 @@
+// generates packet structs, ids and codecs from the protocol.json files in protocol/, see protocol/README.md.
+// Only the types below are understood, packets using anything else are skipped with a comment.
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+
+// same order as ProtocolVersion
+const VERSIONS: [&str; 3] = ["1.20.2", "1.20.4", "1.20.6"];
+const STATES: [&str; 5] = ["handshaking", "status", "login", "configuration", "play"];
+const DIRECTIONS: [(&str, &str); 2] = [("toClient", "clientbound"), ("toServer", "serverbound")];
+
+// (packet name, id per version, layout) for every layout a packet had across the versions
+type Layouts = Vec<(String, [Option<u32>; 3], Value)>;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=protocol");
+    let protocols: Vec<Value> = VERSIONS.iter()
+        .map(|version| {
+            let path = format!("protocol/{version}/protocol.json");
+            let json = fs::read_to_string(&path).unwrap_or_else(|e| panic!("can't read {path}: {e}"));
+            serde_json::from_str(&json).unwrap_or_else(|e| panic!("invalid {path}: {e}"))
+        })
+        .collect();
+
+    let mut out = String::new();
+    for state in STATES {
+        writeln!(out, "pub mod {state} {{").unwrap();
+        for (direction, module) in DIRECTIONS {
+            let layouts = collect_layouts(&protocols, state, direction);
+            if layouts.is_empty() {
+                continue;
+            }
+            writeln!(out, "pub mod {module} {{").unwrap();
+            writeln!(out, "#[allow(unused_imports)]\nuse crate::codec::{{ByteBuffer, McDecode, McEncode, MinecraftReadTypes, MinecraftWriteTypes}};").unwrap();
+            writeln!(out, "use crate::packets::Packet;\nuse crate::version::PacketIds;\n").unwrap();
+            let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+            for (name, _, _) in &layouts {
+                *counts.entry(name.as_str()).or_default() += 1;
+            }
+            for (name, ids, layout) in &layouts {
+                let mut struct_name = pascal_case(name);
+                if counts[name.as_str()] > 1 {
+                    // layout changed between versions, suffix with the first version using it
+                    let first = ids.iter().position(Option::is_some).unwrap();
+                    struct_name.push_str(&format!("V{}", VERSIONS[first].replace('.', "_")));
+                }
+                let mut definitions = String::new();
+                match generate_struct(&struct_name, layout, &mut definitions) {
+                    Ok(()) => {
+                        out.push_str(&definitions);
+                        let ids: Vec<String> = ids.iter()
+                            .map(|id| id.map_or("None".to_string(), |id| format!("Some({id:#04x})")))
+                            .collect();
+                        writeln!(out, "impl Packet for {struct_name} {{").unwrap();
+                        writeln!(out, "    const IDS: PacketIds = PacketIds::from_versions([{}]);\n}}\n", ids.join(", ")).unwrap();
+                    }
+                    Err(unsupported) => writeln!(out, "// {name} skipped: unsupported type {unsupported}\n").unwrap()
+                }
+            }
+            // every packet's ids by its minecraft-data name, including the skipped ones,
+            // so the hand-written packets can be checked against them
+            let mut packet_ids: Vec<(&str, [Option<u32>; 3])> = Vec::new();
+            for (name, ids, _) in &layouts {
+                let merged = match packet_ids.iter_mut().find(|(other, _)| other == name) {
+                    Some((_, merged)) => merged,
+                    None => {
+                        packet_ids.push((name, [None; 3]));
+                        &mut packet_ids.last_mut().unwrap().1
+                    }
+                };
+                for (merged, id) in merged.iter_mut().zip(ids) {
+                    *merged = merged.or(*id);
+                }
+            }
+            writeln!(out, "pub const PACKET_IDS: &[(&str, PacketIds)] = &[").unwrap();
+            for (name, ids) in packet_ids {
+                let ids: Vec<String> = ids.iter()
+                    .map(|id| id.map_or("None".to_string(), |id| format!("Some({id:#04x})")))
+                    .collect();
+                writeln!(out, "    (\"{name}\", PacketIds::from_versions([{}])),", ids.join(", ")).unwrap();
+            }
+            writeln!(out, "];\n").unwrap();
+            writeln!(out, "}}").unwrap();
+        }
+        writeln!(out, "}}\n").unwrap();
+    }
+
+    let path = Path::new(&env::var("OUT_DIR").unwrap()).join("packets.rs");
+    fs::write(path, out).unwrap();
+}
+
+fn collect_layouts(protocols: &[Value], state: &str, direction: &str) -> Layouts {
+    let mut layouts: Layouts = Vec::new();
+    for (version, protocol) in protocols.iter().enumerate() {
+        let types = &protocol[state][direction]["types"];
+        let fields = types["packet"][1].as_array().expect("packet container");
+        let mappings = fields[0]["type"][1]["mappings"].as_object().expect("packet id mapper");
+        let switch = fields[1]["type"][1]["fields"].as_object().expect("packet switch");
+        for (id, name) in mappings {
+            let name = name.as_str().unwrap();
+            let id = u32::from_str_radix(id.trim_start_matches("0x"), 16).expect("hex packet id");
+            let layout = &types[switch[name].as_str().unwrap()];
+            match layouts.iter_mut().find(|(other, _, other_layout)| other == name && other_layout == layout) {
+                Some((_, ids, _)) => ids[version] = Some(id),
+                None => {
+                    let mut ids = [None; 3];
+                    ids[version] = Some(id);
+                    layouts.push((name.to_string(), ids, layout.clone()));
+                }
+            }
+        }
+    }
+    layouts
+}
+
+// appends the struct and its codec, plus any nested container structs, to `out`
+fn generate_struct(name: &str, layout: &Value, out: &mut String) -> Result<(), String> {
+    let fields = container_fields(layout)?;
+    let mut declarations = Vec::new();
+    let mut encode = String::new();
+    let mut decode = Vec::new();
+    for (field_name, ty) in &fields {
+        let field = snake_case(field_name);
+        let rust_type = rust_type(ty, &format!("{name}{}", pascal_case(&snake_case(field_name))), out)?;
+        declarations.push(format!("    pub {field}: {rust_type}"));
+        encode.push_str(&encode_value(ty, &format!("self.{field}"))?);
+        decode.push(format!("            {field}: {}", decode_value(ty)?));
+    }
+
+    writeln!(out, "#[derive(Debug, Clone, PartialEq)]").unwrap();
+    if fields.is_empty() {
+        writeln!(out, "pub struct {name};\n").unwrap();
+    } else {
+        writeln!(out, "pub struct {name} {{\n{}\n}}\n", declarations.join(",\n")).unwrap();
+    }
+    writeln!(out, "impl McEncode for {name} {{").unwrap();
+    writeln!(out, "    #[allow(unused_variables)]\n    fn encode(&self, buffer: &mut ByteBuffer) -> crate::error::Result<()> {{\n{encode}        Ok(())\n    }}\n}}\n").unwrap();
+    writeln!(out, "impl McDecode for {name} {{").unwrap();
+    writeln!(out, "    #[allow(unused_variables)]\n    fn decode(buffer: &mut ByteBuffer) -> crate::error::Result<Self> {{").unwrap();
+    if fields.is_empty() {
+        writeln!(out, "        Ok({name})\n    }}\n}}\n").unwrap();
+    } else {
+        writeln!(out, "        Ok({name} {{\n{}\n        }})\n    }}\n}}\n", decode.join(",\n")).unwrap();
+    }
+    Ok(())
+}
+
+fn container_fields(layout: &Value) -> Result<Vec<(String, Value)>, String> {
+    if layout[0] != "container" {
+        return Err(layout.to_string());
+    }
+    layout[1].as_array()
+        .ok_or_else(|| layout.to_string())?
+        .iter()
+        .map(|field| match field["name"].as_str() {
+            Some(name) => Ok((name.to_string(), field["type"].clone())),
+            None => Err(format!("anonymous field {field}"))
+        })
+        .collect()
+}
+
+fn primitive(ty: &str) -> Option<&'static str> {
+    Some(match ty {
+        "bool" => "bool",
+        "i8" => "i8",
+        "u8" => "u8",
+        "i16" => "i16",
+        "u16" => "u16",
+        "i32" => "i32",
+        "u32" => "u32",
+        "i64" => "i64",
+        "u64" => "u64",
+        "f32" => "f32",
+        "f64" => "f64",
+        "string" => "String",
+        "UUID" => "uuid::Uuid",
+        "anonymousNbt" => "fastnbt::Value",
+        _ => return None
+    })
+}
+
+fn is_varint_counted(options: &Value) -> bool {
+    options["countType"] == "varint"
+}
+
+fn rust_type(ty: &Value, nested_name: &str, out: &mut String) -> Result<String, String> {
+    if let Some(ty) = ty.as_str() {
+        return match ty {
+            "varint" => Ok("i32".to_string()),
+            "restBuffer" => Ok("Vec<u8>".to_string()),
+            _ => primitive(ty).map(str::to_string).ok_or_else(|| ty.to_string())
+        };
+    }
+    match ty[0].as_str() {
+        Some("pstring") if is_varint_counted(&ty[1]) => Ok("String".to_string()),
+        Some("buffer") if is_varint_counted(&ty[1]) => Ok("Vec<u8>".to_string()),
+        Some("array") if is_varint_counted(&ty[1]) => Ok(format!("Vec<{}>", rust_type(&ty[1]["type"], nested_name, out)?)),
+        Some("option") => Ok(format!("Option<{}>", rust_type(&ty[1], nested_name, out)?)),
+        Some("container") => {
+            generate_struct(nested_name, ty, out)?;
+            Ok(nested_name.to_string())
+        }
+        _ => Err(ty.to_string())
+    }
+}
+
+// `value` is a place expression of type T
+fn encode_value(ty: &Value, value: &str) -> Result<String, String> {
+    let reference = match value.strip_prefix('*') {
+        Some(reference) => reference.to_string(),
+        None => format!("&{value}")
+    };
+    if let Some(ty) = ty.as_str() {
+        return match ty {
+            "varint" => Ok(format!("        buffer.write_var_int({value} as u32);\n")),
+            _ => Ok(format!("        McEncode::encode({reference}, buffer)?;\n"))
+        };
+    }
+    // method calls auto-deref, so the receiver doesn't need the explicit `*`
+    let receiver = value.trim_start_matches('*');
+    match ty[0].as_str() {
+        Some("buffer") => Ok(format!("        buffer.write_var_int({receiver}.len() as u32);\n        buffer.write_bytes({reference});\n")),
+        Some("array") => Ok(format!(
+            "        buffer.write_var_int({receiver}.len() as u32);\n        for element in {receiver}.iter() {{\n{}        }}\n",
+            encode_value(&ty[1]["type"], "*element")?
+        )),
+        Some("option") => Ok(format!(
+            "        match &{value} {{\n            Some(value) => {{\n                McEncode::encode(&true, buffer)?;\n{}            }}\n            None => McEncode::encode(&false, buffer)?\n        }}\n",
+            encode_value(&ty[1], "*value")?
+        )),
+        _ => Ok(format!("        McEncode::encode({reference}, buffer)?;\n"))
+    }
+}
+
+fn decode_value(ty: &Value) -> Result<String, String> {
+    if let Some(ty) = ty.as_str() {
+        return match ty {
+            "varint" => Ok("buffer.read_var_int()? as i32".to_string()),
+            "restBuffer" => Ok("<Vec<u8> as McDecode>::decode(buffer)?".to_string()),
+            _ => primitive(ty).map(|rust_type| format!("<{rust_type} as McDecode>::decode(buffer)?")).ok_or_else(|| ty.to_string())
+        };
+    }
+    match ty[0].as_str() {
+        Some("pstring") => Ok("<String as McDecode>::decode(buffer)?".to_string()),
+        // lengths are checked against what's left of the packet before anything is allocated
+        Some("buffer") => Ok(
+            "{ let length = buffer.read_var_int()? as usize; crate::codec::check_array_length(length, buffer.readabe_bytes())?; buffer.read_bytes(length)? }"
+                .to_string()
+        ),
+        Some("array") => Ok(format!(
+            "{{ let length = buffer.read_var_int()? as usize; crate::codec::check_array_length(length, buffer.readabe_bytes())?; \
+             let mut elements = Vec::with_capacity(length); for _ in 0..length {{ elements.push({}); }} elements }}",
+            decode_value(&ty[1]["type"])?
+        )),
+        Some("option") => Ok(format!("if <bool as McDecode>::decode(buffer)? {{ Some({}) }} else {{ None }}", decode_value(&ty[1])?)),
+        // nested containers were already generated by rust_type, decode them by their own impl
+        Some("container") => Ok("McDecode::decode(buffer)?".to_string()),
+        _ => Err(ty.to_string())
+    }
+}
+
+fn snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut snake = String::new();
+    for (index, &c) in chars.iter().enumerate() {
+        if c.is_ascii_uppercase() && index > 0 {
+            let previous = chars[index - 1];
+            let next_is_lower = chars.get(index + 1).is_some_and(|next| next.is_ascii_lowercase());
+            if previous.is_ascii_lowercase() || previous.is_ascii_digit() || (previous.is_ascii_uppercase() && next_is_lower) {
+                snake.push('_');
+            }
+        }
+        snake.push(c.to_ascii_lowercase());
+    }
+    match snake.as_str() {
+        "type" | "match" | "move" | "ref" | "loop" | "use" | "mod" | "fn" | "impl" | "struct" | "enum" => format!("r#{snake}"),
+        _ => snake
+    }
+}
+
+fn pascal_case(name: &str) -> String {
+    name.trim_start_matches("r#")
+        .split('_')
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new()
+            }
+        })
+        .collect()
+}
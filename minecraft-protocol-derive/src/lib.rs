@@ -0,0 +1,293 @@
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Attribute, Data, DeriveInput, Fields, GenericArgument, LitInt, PathArguments, Type};
+
+// #[mc(...)] options that change how a single field is put on the wire
+#[derive(Clone, Copy, Default)]
+struct FieldOptions {
+    varint: bool,
+    length_prefixed: bool,
+    optional: bool,
+    // not part of the default layout, decoded as Default::default()
+    skip: bool
+}
+
+fn field_options(attrs: &[Attribute]) -> syn::Result<FieldOptions> {
+    let mut options = FieldOptions::default();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("mc")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("varint") {
+                options.varint = true;
+            } else if meta.path.is_ident("length_prefixed") {
+                options.length_prefixed = true;
+            } else if meta.path.is_ident("optional") {
+                options.optional = true;
+            } else if meta.path.is_ident("skip") {
+                options.skip = true;
+            } else {
+                return Err(meta.error("expected `varint`, `length_prefixed`, `optional` or `skip`"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(options)
+}
+
+fn variant_id(attrs: &[Attribute]) -> syn::Result<Option<u32>> {
+    let mut id = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("mc")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("id") {
+                let value: LitInt = meta.value()?.parse()?;
+                id = Some(value.base10_parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `id = <number>`"))
+            }
+        })?;
+    }
+    Ok(id)
+}
+
+// first generic argument of `Option<T>` / `Vec<T>`
+fn inner_type(ty: &Type) -> syn::Result<&Type> {
+    if let Type::Path(path) = ty {
+        if let Some(segment) = path.path.segments.last() {
+            if let PathArguments::AngleBracketed(arguments) = &segment.arguments {
+                if let Some(GenericArgument::Type(inner)) = arguments.args.first() {
+                    return Ok(inner);
+                }
+            }
+        }
+    }
+    Err(syn::Error::new_spanned(ty, "expected a type with a generic argument"))
+}
+
+fn encode_value(value: TokenStream2, ty: &Type, options: FieldOptions) -> syn::Result<TokenStream2> {
+    if options.skip {
+        return Ok(TokenStream2::new());
+    }
+    if options.optional {
+        let inner = inner_type(ty)?;
+        let encode_inner = encode_value(quote!(inner), inner, FieldOptions { optional: false, ..options })?;
+        return Ok(quote! {
+            match #value {
+                Some(inner) => {
+                    ::minecraft_protocol::codec::McEncode::encode(&true, buffer)?;
+                    #encode_inner
+                }
+                None => ::minecraft_protocol::codec::McEncode::encode(&false, buffer)?
+            }
+        });
+    }
+    if options.length_prefixed {
+        let inner = inner_type(ty)?;
+        let encode_element = encode_value(quote!(element), inner, FieldOptions { length_prefixed: false, ..options })?;
+        return Ok(quote! {
+            {
+                let value = #value;
+                ::minecraft_protocol::codec::MinecraftWriteTypes::write_var_int(buffer, value.len() as u32);
+                for element in value.iter() {
+                    #encode_element
+                }
+            }
+        });
+    }
+    if options.varint {
+        return Ok(quote! {
+            ::minecraft_protocol::codec::MinecraftWriteTypes::write_var_int(buffer, *#value as u32);
+        });
+    }
+    Ok(quote! {
+        ::minecraft_protocol::codec::McEncode::encode(#value, buffer)?;
+    })
+}
+
+fn decode_value(ty: &Type, options: FieldOptions) -> syn::Result<TokenStream2> {
+    if options.skip {
+        return Ok(quote!(<#ty as ::core::default::Default>::default()));
+    }
+    if options.optional {
+        let inner = inner_type(ty)?;
+        let decode_inner = decode_value(inner, FieldOptions { optional: false, ..options })?;
+        return Ok(quote! {
+            if <bool as ::minecraft_protocol::codec::McDecode>::decode(buffer)? {
+                Some(#decode_inner)
+            } else {
+                None
+            }
+        });
+    }
+    if options.length_prefixed {
+        let inner = inner_type(ty)?;
+        let decode_element = decode_value(inner, FieldOptions { length_prefixed: false, ..options })?;
+        return Ok(quote! {
+            {
+                let length = ::minecraft_protocol::codec::MinecraftReadTypes::read_var_int(buffer)? as usize;
+                ::minecraft_protocol::codec::check_array_length(length, ::minecraft_protocol::codec::MinecraftReadTypes::readabe_bytes(buffer))?;
+                let mut elements = Vec::with_capacity(length);
+                for _ in 0..length {
+                    elements.push(#decode_element);
+                }
+                elements
+            }
+        });
+    }
+    if options.varint {
+        return Ok(quote! {
+            ::minecraft_protocol::codec::MinecraftReadTypes::read_var_int(buffer)? as #ty
+        });
+    }
+    Ok(quote! {
+        <#ty as ::minecraft_protocol::codec::McDecode>::decode(buffer)?
+    })
+}
+
+// bindings are prefixed so that field names can't shadow `buffer` in the generated code
+fn field_bindings(fields: &Fields) -> Vec<syn::Ident> {
+    fields.iter().enumerate()
+        .map(|(index, field)| match &field.ident {
+            Some(name) => format_ident!("field_{}", name),
+            None => format_ident!("field_{}", index)
+        })
+        .collect()
+}
+
+fn encode_fields(fields: &Fields, bindings: &[syn::Ident]) -> syn::Result<TokenStream2> {
+    let mut encoded = TokenStream2::new();
+    for (field, binding) in fields.iter().zip(bindings) {
+        encoded.extend(encode_value(quote!(#binding), &field.ty, field_options(&field.attrs)?)?);
+    }
+    Ok(encoded)
+}
+
+fn destructure(path: TokenStream2, fields: &Fields, bindings: &[syn::Ident]) -> TokenStream2 {
+    match fields {
+        Fields::Named(_) => {
+            let names = fields.iter().map(|field| &field.ident);
+            quote!(#path { #(#names: #bindings),* })
+        }
+        Fields::Unnamed(_) => quote!(#path ( #(#bindings),* )),
+        Fields::Unit => quote!(#path)
+    }
+}
+
+fn construct(path: TokenStream2, fields: &Fields) -> syn::Result<TokenStream2> {
+    let mut values = Vec::new();
+    for field in fields.iter() {
+        values.push(decode_value(&field.ty, field_options(&field.attrs)?)?);
+    }
+    Ok(match fields {
+        Fields::Named(_) => {
+            let names = fields.iter().map(|field| &field.ident);
+            quote!(#path { #(#names: #values),* })
+        }
+        Fields::Unnamed(_) => quote!(#path ( #(#values),* )),
+        Fields::Unit => quote!(#path)
+    })
+}
+
+fn variant_ids(data: &syn::DataEnum) -> syn::Result<Vec<u32>> {
+    let mut next = 0;
+    let mut ids = Vec::new();
+    for variant in &data.variants {
+        let id = variant_id(&variant.attrs)?.unwrap_or(next);
+        ids.push(id);
+        next = id + 1;
+    }
+    Ok(ids)
+}
+
+fn expand_encode(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let bindings = field_bindings(&data.fields);
+            let pattern = destructure(quote!(Self), &data.fields, &bindings);
+            let encoded = encode_fields(&data.fields, &bindings)?;
+            quote! {
+                let #pattern = self;
+                #encoded
+            }
+        }
+        Data::Enum(data) => {
+            let mut arms = Vec::new();
+            for (variant, id) in data.variants.iter().zip(variant_ids(data)?) {
+                let name = &variant.ident;
+                let bindings = field_bindings(&variant.fields);
+                let pattern = destructure(quote!(Self::#name), &variant.fields, &bindings);
+                let encoded = encode_fields(&variant.fields, &bindings)?;
+                arms.push(quote! {
+                    #pattern => {
+                        ::minecraft_protocol::codec::MinecraftWriteTypes::write_var_int(buffer, #id);
+                        #encoded
+                    }
+                });
+            }
+            quote! {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+        Data::Union(_) => return Err(syn::Error::new(Span::call_site(), "McEncode cannot be derived for unions"))
+    };
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::minecraft_protocol::codec::McEncode for #name #type_generics #where_clause {
+            #[allow(unused_variables)]
+            fn encode(&self, buffer: &mut ::minecraft_protocol::codec::ByteBuffer) -> ::minecraft_protocol::error::Result<()> {
+                #body
+                Ok(())
+            }
+        }
+    })
+}
+
+fn expand_decode(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let value = construct(quote!(Self), &data.fields)?;
+            quote!(Ok(#value))
+        }
+        Data::Enum(data) => {
+            let mut arms = Vec::new();
+            for (variant, id) in data.variants.iter().zip(variant_ids(data)?) {
+                let variant_name = &variant.ident;
+                let value = construct(quote!(Self::#variant_name), &variant.fields)?;
+                arms.push(quote!(#id => Ok(#value),));
+            }
+            let type_name = name.to_string();
+            quote! {
+                match ::minecraft_protocol::codec::MinecraftReadTypes::read_var_int(buffer)? {
+                    #(#arms)*
+                    other => Err(::minecraft_protocol::error::ProtocolError::UnknownVariant { name: #type_name, id: other })
+                }
+            }
+        }
+        Data::Union(_) => return Err(syn::Error::new(Span::call_site(), "McDecode cannot be derived for unions"))
+    };
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::minecraft_protocol::codec::McDecode for #name #type_generics #where_clause {
+            #[allow(unused_variables)]
+            fn decode(buffer: &mut ::minecraft_protocol::codec::ByteBuffer) -> ::minecraft_protocol::error::Result<Self> {
+                #body
+            }
+        }
+    })
+}
+
+#[proc_macro_derive(McEncode, attributes(mc))]
+pub fn derive_encode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_encode(&input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+#[proc_macro_derive(McDecode, attributes(mc))]
+pub fn derive_decode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_decode(&input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
@@ -0,0 +1,676 @@
+use std::net::SocketAddr;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use bytebuffer::ByteBuffer;
+use futures::future::try_join_all;
+use uuid::Uuid;
+use once_cell::sync::{Lazy, OnceCell};
+
+use tokio::io::{self, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use minecraft_protocol::codec::MinecraftReadTypes;
+use minecraft_protocol::configuration::{Configuration, ConfigurationStage};
+use minecraft_protocol::config::{ConfigError, ForwardingMode, ServerConfig, DEFAULT_CONFIG_PATH};
+use minecraft_protocol::connection::{keep_alive_interval, Connection, PacketSender};
+use minecraft_protocol::encryption::{generate_verify_token, server_hash, ServerKey, VERIFY_TOKEN_LENGTH};
+use minecraft_protocol::error::{ProtocolError, Result};
+use minecraft_protocol::forwarding::{parse_bungeecord, parse_velocity, velocity_request_data, VELOCITY_CHANNEL};
+use minecraft_protocol::state::ConnectionState;
+use minecraft_protocol::legacy::{is_legacy_ping, read_legacy_ping};
+use minecraft_protocol::login_plugin::LoginPluginRequests;
+use minecraft_protocol::packets::configuration::{ClientboundConfigurationPacket, ServerboundConfigurationPacket};
+use minecraft_protocol::packets::handshaking::ServerboundHandshakingPacket;
+use minecraft_protocol::packets::login::{EncryptionRequest, EncryptionResponse, LoginDisconnect, LoginSuccess, ServerboundLoginPacket};
+use minecraft_protocol::packets::play::{
+    BlockUpdate, ChunkDataAndUpdateLight, ClientboundKeepAlive, EntityMetadata, GameEvent, LoginPlay, MetadataValue, PlayDisconnect,
+    ServerboundPlayPacket, SetCamera, SetCenterChunk, SetEntityMetadata, SpawnEntity, SynchronizePlayerPosition
+};
+use minecraft_protocol::packets::status::{PingResponse, ServerboundStatusPacket, StatusResponse};
+use minecraft_protocol::proxy_protocol::read_proxy_header;
+use minecraft_protocol::registry::{Registries, RegistryBuilder, REGISTRY};
+use minecraft_protocol::server_state::{OnlinePlayer, ServerState};
+use minecraft_protocol::session::{is_valid_username, MojangSessionVerifier, SessionVerifier};
+use minecraft_protocol::status::{load_favicon, MinecraftStatus, StatusVersion};
+use minecraft_protocol::text::TextComponent;
+use minecraft_protocol::types::{Angle, Identifier};
+use minecraft_protocol::version::ProtocolVersion;
+use minecraft_protocol::world::{empty_chunk_sections, HEIGHT_MAP};
+
+static CONFIG: OnceCell<ServerConfig> = OnceCell::new();
+// favicon data url, read once at startup
+static FAVICON: OnceCell<Option<String>> = OnceCell::new();
+static CONFIGURATION: OnceCell<ConfigurationStage> = OnceCell::new();
+static SERVER_KEY: Lazy<ServerKey> = Lazy::new(|| ServerKey::generate().expect("failed to generate server key"));
+static SESSION_VERIFIER: Lazy<MojangSessionVerifier> = Lazy::new(MojangSessionVerifier::new);
+
+// answers with the client's own version when we support it, so any of them shows as compatible
+fn status(version: ProtocolVersion, server: &ServerState) -> MinecraftStatus {
+    MinecraftStatus {
+        version: StatusVersion { name: version.name().to_string(), protocol: version.protocol() as i32 },
+        players: Some(server.status_players(config().max_players as i32, config().hide_player_list)),
+        description: config().motd.clone(),
+        favicon: FAVICON.get().cloned().flatten(),
+        enforces_secure_chat: Some(false),
+        previews_chat: None
+    }
+}
+
+// what we need to remember between Login Start and Encryption Response
+struct PendingLogin {
+    username: String,
+    verify_token: [u8; VERIFY_TOKEN_LENGTH]
+}
+
+// per connection state, the player only counts as online once it reaches play
+struct Client {
+    state: ConnectionState,
+    pending_login: Option<PendingLogin>,
+    plugin_requests: LoginPluginRequests,
+    // held back until every login plugin request is answered
+    login_success: Option<LoginSuccess>,
+    configuration: Option<Configuration>,
+    profile: Option<(Uuid, String)>,
+    player: Option<OnlinePlayer>
+}
+
+fn config() -> &'static ServerConfig {
+    CONFIG.get().expect("config is loaded before accepting connections")
+}
+
+fn max_players() -> usize {
+    config().max_players as usize
+}
+
+// the path can be given as the first argument, without a server.toml the defaults are used
+fn load_config() -> std::result::Result<ServerConfig, ConfigError> {
+    match std::env::args().nth(1) {
+        Some(path) => ServerConfig::load(path),
+        None if !Path::new(DEFAULT_CONFIG_PATH).exists() => {
+            println!("{DEFAULT_CONFIG_PATH} not found, using the default config");
+            Ok(ServerConfig::default())
+        }
+        None => ServerConfig::load(DEFAULT_CONFIG_PATH)
+    }
+}
+
+fn load_server_favicon(path: &Path) -> Option<String> {
+    if !path.exists() {
+        return None;
+    }
+    match load_favicon(path) {
+        Ok(favicon) => Some(favicon),
+        Err(e) => {
+            println!("skipping favicon {}: {e}", path.display());
+            None
+        }
+    }
+}
+
+fn load_registries(datapacks: &[PathBuf]) -> Result<Registries> {
+    let mut builder = RegistryBuilder::from(REGISTRY.clone());
+    for datapack in datapacks {
+        builder = builder.datapack(datapack)?;
+    }
+    Ok(builder.build())
+}
+
+fn configuration() -> &'static ConfigurationStage {
+    CONFIGURATION.get().expect("configuration stage is set up before accepting connections")
+}
+
+fn now() -> u128 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis()
+}
+
+#[tokio::main]
+async fn main() -> io::Result<()> {
+    println!("starting server");
+    let config = match load_config() {
+        Ok(config) => CONFIG.get_or_init(|| config),
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    };
+    FAVICON.get_or_init(|| load_server_favicon(&config.favicon));
+    let registries = match load_registries(&config.datapacks) {
+        Ok(registries) => registries,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    };
+    CONFIGURATION.get_or_init(|| {
+        let mut stage = ConfigurationStage::new();
+        stage.brand = Some(config.brand.clone());
+        stage.registries = registries;
+        stage
+    });
+    let mut listeners = Vec::new();
+    for address in &config.listen {
+        listeners.push(TcpListener::bind(address).await?);
+        println!("listening on {address}");
+    }
+    let server = ServerState::new();
+    println!("started server");
+    try_join_all(listeners.into_iter().map(|listener| accept(listener, server.clone()))).await?;
+    Ok(())
+}
+
+async fn accept(listener: TcpListener, server: ServerState) -> io::Result<()> {
+    loop {
+        let (socket, address) = listener.accept().await?;
+        tokio::spawn(handle_connection(socket, address, server.clone()));
+    }
+}
+
+// pre 1.7 clients ping with a different format, they get a single kick packet and the connection is closed
+async fn answer_legacy_ping(socket: &mut TcpStream, server: &ServerState) -> Result<()> {
+    let ping = read_legacy_ping(socket).await?;
+    socket.write_all(&ping.response(&status(ProtocolVersion::default(), server))).await?;
+    Ok(socket.shutdown().await?)
+}
+
+// the client's address from the PROXY protocol header, None when the balancer didn't send one
+async fn read_source_address(socket: &mut TcpStream, address: SocketAddr) -> Result<Option<SocketAddr>> {
+    if !config().is_trusted_proxy(address.ip()) {
+        return Err(ProtocolError::InvalidProxyHeader(format!("{} is not a trusted proxy", address.ip())));
+    }
+    Ok(read_proxy_header(socket).await?.source)
+}
+
+async fn handle_connection(mut socket: TcpStream, address: SocketAddr, server: ServerState) {
+    let mut source_address = None;
+    if config().proxy_protocol {
+        match read_source_address(&mut socket, address).await {
+            Ok(address) => source_address = address,
+            Err(e) => {
+                println!("{e}");
+                return;
+            }
+        }
+    }
+    if is_legacy_ping(&socket).await {
+        if let Err(e) = answer_legacy_ping(&mut socket, &server).await {
+            println!("{e}");
+        }
+        return;
+    }
+    let mut connection = Connection::new(socket);
+    if let Some(address) = source_address {
+        connection.set_peer_address(address);
+    }
+    let sender = connection.sender().clone();
+    let mut client = Client {
+        state: ConnectionState::Handshaking,
+        pending_login: None,
+        plugin_requests: LoginPluginRequests::new(),
+        login_success: None,
+        configuration: None,
+        profile: None,
+        player: None
+    };
+    let mut keep_alive = keep_alive_interval();
+    loop {
+        let mut frame = tokio::select! {
+            frame = connection.read_frame() => match frame {
+                Some(Ok(frame)) => frame,
+                Some(Err(e)) => {
+                    println!("{e}");
+                    return;
+                }
+                None => return
+            },
+            _ = keep_alive.tick(), if client.state == ConnectionState::Play => {
+                if let Err(e) = sender.send(&ClientboundKeepAlive { keep_alive_id: now() as u64 }).await {
+                    println!("{}", close_reason(e, &mut connection).await);
+                    return;
+                }
+                continue;
+            }
+        };
+        let previous_state = client.state;
+        if let Err(e) = handle_packet(&mut client, &mut frame, &mut connection, &server).await {
+            println!("disconnecting: {}", close_reason(e, &mut connection).await);
+            return;
+        }
+        if previous_state != ConnectionState::Play && client.state == ConnectionState::Play {
+            keep_alive.reset();
+        }
+    }
+}
+
+// a send only says the writer has stopped, the connection knows why
+async fn close_reason(error: ProtocolError, connection: &mut Connection) -> ProtocolError {
+    match error {
+        ProtocolError::ConnectionClosed => connection.close_reason().await,
+        error => error
+    }
+}
+
+fn is_expected(state: ConnectionState, version: ProtocolVersion, packet_id: u32) -> bool {
+    match state {
+        ConnectionState::Handshaking => ServerboundHandshakingPacket::contains(version, packet_id),
+        ConnectionState::Status => ServerboundStatusPacket::contains(version, packet_id),
+        ConnectionState::Login => ServerboundLoginPacket::contains(version, packet_id),
+        ConnectionState::Configuration => ServerboundConfigurationPacket::contains(version, packet_id),
+        // the demo only handles movement in play, other valid play packets are skipped
+        ConnectionState::Play => packet_id < version.serverbound_play_packet_count()
+    }
+}
+
+async fn handle_packet(
+    client: &mut Client,
+    packet_buffer: &mut ByteBuffer,
+    connection: &mut Connection,
+    server: &ServerState
+) -> Result<()> {
+    let state = &mut client.state;
+    let sender = connection.sender().clone();
+    let version = connection.version();
+    let packet_id = packet_buffer.read_var_int()?;
+    if !is_expected(*state, version, packet_id) {
+        return Err(ProtocolError::UnknownPacket { state: *state, id: packet_id });
+    }
+    match state {
+        ConnectionState::Handshaking => match ServerboundHandshakingPacket::decode(version, packet_id, packet_buffer)? {
+            ServerboundHandshakingPacket::Handshake(handshake) => {
+                let next_state = ConnectionState::from_next_state(handshake.next_state, handshake.protocol_version)?;
+                match ProtocolVersion::from_protocol(handshake.protocol_version) {
+                    Some(version) => connection.set_version(version),
+                    // status still answers so the client can show which version we expect
+                    None if next_state == ConnectionState::Login => {
+                        state.transition(next_state)?;
+                        sender.send(&LoginDisconnect { reason: version_rejection(handshake.protocol_version) }).await?;
+                        return Err(ProtocolError::UnsupportedVersion(handshake.protocol_version));
+                    }
+                    None => {}
+                }
+                state.transition(next_state)?;
+                if next_state == ConnectionState::Login && config().forwarding == ForwardingMode::BungeeCord {
+                    match parse_bungeecord(&handshake.server_address) {
+                        Ok((_, forwarded)) => connection.set_forwarded(forwarded),
+                        Err(e) => {
+                            let reason = TextComponent::text("If you wish to use IP forwarding, please enable it in your BungeeCord config as well!");
+                            sender.send(&LoginDisconnect { reason }).await?;
+                            return Err(e);
+                        }
+                    }
+                }
+            }
+        },
+        ConnectionState::Status => match ServerboundStatusPacket::decode(version, packet_id, packet_buffer)? {
+            ServerboundStatusPacket::StatusRequest(_) => {
+                sender.send(&StatusResponse { json_response: status(version, server).to_json() }).await?;
+            }
+            ServerboundStatusPacket::PingRequest(ping) => {
+                sender.send(&PingResponse { payload: ping.payload }).await?;
+            }
+        },
+        ConnectionState::Login => match ServerboundLoginPacket::decode(version, packet_id, packet_buffer)? {
+            ServerboundLoginPacket::LoginStart(login_start) => {
+                // the login is already underway or done
+                if client.profile.is_some() || client.pending_login.is_some() || client.plugin_requests.is_waiting() {
+                    return Err(ProtocolError::UnknownPacket { state: *state, id: packet_id });
+                }
+                if !is_valid_username(&login_start.name) {
+                    return reject_login(&sender, TextComponent::text("Invalid username")).await;
+                }
+                // velocity sends the player's data only when asked for it
+                if config().forwarding == ForwardingMode::Velocity {
+                    let request = client.plugin_requests.request(Identifier::parse(VELOCITY_CHANNEL)?, velocity_request_data());
+                    return sender.send(&request).await;
+                }
+                // a forwarding proxy has already authenticated the player and knows the real uuid
+                let (uuid, properties) = match connection.forwarded() {
+                    Some(forwarded) => (forwarded.uuid, forwarded.properties.clone()),
+                    None => (login_start.uuid, vec![])
+                };
+                if config().online_mode {
+                    // rejected before authenticating if we can, the real uuid is only known afterwards
+                    if let Err(e) = server.check_join(uuid, max_players()) {
+                        return reject_login(&sender, e.reason()).await;
+                    }
+                    let verify_token = generate_verify_token();
+                    let encryption_request = EncryptionRequest {
+                        server_id: String::new(),
+                        public_key: SERVER_KEY.public_key_der().to_vec(),
+                        verify_token: verify_token.to_vec(),
+                        // the session service is always asked, vanilla only skips it for local players
+                        should_authenticate: true
+                    };
+                    sender.send(&encryption_request).await?;
+                    client.pending_login = Some(PendingLogin { username: login_start.name, verify_token });
+                    return Ok(());
+                }
+                let login_success = LoginSuccess {
+                    uuid,
+                    username: login_start.name,
+                    properties,
+                    strict_error_handling: false
+                };
+                complete_login(&mut client.profile, connection, server, login_success).await?;
+            }
+            ServerboundLoginPacket::EncryptionResponse(encryption_response) => {
+                let Some(pending_login) = client.pending_login.take() else {
+                    return Err(ProtocolError::UnknownPacket { state: *state, id: packet_id });
+                };
+                let login_success = match authenticate(&*SESSION_VERIFIER, connection, pending_login, encryption_response).await {
+                    Ok(login_success) => login_success,
+                    Err(e) => {
+                        println!("{e}");
+                        return reject_login(&sender, TextComponent::text("Failed to verify username!")).await;
+                    }
+                };
+                complete_login(&mut client.profile, connection, server, login_success).await?;
+            }
+            ServerboundLoginPacket::LoginPluginResponse(response) => {
+                let (channel, data) = client.plugin_requests.response(response)?;
+                // responses on other channels don't affect the login
+                if channel == Identifier::parse(VELOCITY_CHANNEL)? {
+                    // clients that didn't come through velocity don't understand the channel
+                    let Some(data) = data else {
+                        return reject_login(&sender, TextComponent::text("This server requires you to connect with Velocity.")).await;
+                    };
+                    let forwarded = match parse_velocity(&data, config().forwarding_secret.as_bytes()) {
+                        Ok(forwarded) => forwarded,
+                        Err(e) => {
+                            println!("{e}");
+                            return reject_login(&sender, TextComponent::text("Unable to verify player details")).await;
+                        }
+                    };
+                    client.login_success = Some(LoginSuccess {
+                        uuid: forwarded.uuid,
+                        username: forwarded.name.clone().unwrap_or_default(),
+                        properties: forwarded.properties.clone(),
+                        strict_error_handling: false
+                    });
+                    connection.set_forwarded(forwarded);
+                }
+                if client.plugin_requests.is_waiting() {
+                    return Ok(());
+                }
+                if let Some(login_success) = client.login_success.take() {
+                    complete_login(&mut client.profile, connection, server, login_success).await?;
+                }
+            }
+            ServerboundLoginPacket::LoginAcknowledged(_) => {
+                // only valid as the answer to Login Success
+                if client.profile.is_none() {
+                    return Err(ProtocolError::UnknownPacket { state: *state, id: packet_id });
+                }
+                state.transition(ConnectionState::Configuration)?;
+                let mut outgoing = Vec::new();
+                client.configuration = Some(configuration().start(version, &mut outgoing)?);
+                send_all(&sender, version, outgoing).await?;
+            }
+        },
+        ConnectionState::Configuration => {
+            let packet = ServerboundConfigurationPacket::decode(version, packet_id, packet_buffer)?;
+            let configuration = client.configuration.as_mut().expect("configuration starts with Login Acknowledged");
+            let mut outgoing = Vec::new();
+            configuration.handle(&packet, &mut outgoing)?;
+            send_all(&sender, version, outgoing).await?;
+            if let ServerboundConfigurationPacket::AcknowledgeFinishConfiguration(_) = packet {
+                state.transition(ConnectionState::Play)?;
+                let (uuid, username) = client.profile.clone().expect("Login Acknowledged is only accepted after Login Success");
+                // another login can have taken the last slot or the same uuid since Login Start
+                match server.try_join(uuid, username.clone(), max_players()) {
+                    Ok(player) => {
+                        client.player = Some(player);
+                        let brand = configuration.client().brand.as_deref().unwrap_or("an unknown client");
+                        if let Some(address) = connection.client_address() {
+                            println!("{username} joined from {address} using {brand}");
+                        }
+                    }
+                    Err(e) => {
+                        sender.send(&PlayDisconnect { reason: e.reason() }).await?;
+                        return Err(ProtocolError::Disconnected(e.reason().to_plain()));
+                    }
+                }
+                join_game(&sender).await?;
+            }
+        },
+        ConnectionState::Play => {
+            if !ServerboundPlayPacket::contains(version, packet_id) {
+                return Ok(());
+            }
+            match ServerboundPlayPacket::decode(version, packet_id, packet_buffer)? {
+                ServerboundPlayPacket::ConfirmTeleportation(_) => {}
+                ServerboundPlayPacket::ServerboundKeepAlive(_) => {}
+                ServerboundPlayPacket::SetPlayerPosition(_) => {}
+                ServerboundPlayPacket::SetPlayerPositionAndRotation(_) => {}
+                ServerboundPlayPacket::SetPlayerRotation(_) => {}
+                ServerboundPlayPacket::SetPlayerOnGround(_) => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn send_all(sender: &PacketSender, version: ProtocolVersion, packets: Vec<ClientboundConfigurationPacket>) -> Result<()> {
+    for packet in packets {
+        sender.send_raw(packet.encode_packet(version)?).await?;
+    }
+    Ok(())
+}
+
+// last admission check before Login Success, the player's uuid is final by now
+async fn complete_login(
+    profile: &mut Option<(Uuid, String)>,
+    connection: &mut Connection,
+    server: &ServerState,
+    login_success: LoginSuccess
+) -> Result<()> {
+    if let Err(e) = server.check_join(login_success.uuid, max_players()) {
+        return reject_login(connection.sender(), e.reason()).await;
+    }
+    *profile = Some((login_success.uuid, login_success.username.clone()));
+    finish_login(connection, login_success).await
+}
+
+// sends the reason as Login Disconnect, the returned error then closes the connection
+async fn reject_login(sender: &PacketSender, reason: TextComponent) -> Result<()> {
+    let message = reason.to_plain();
+    sender.send(&LoginDisconnect { reason }).await?;
+    Err(ProtocolError::Disconnected(message))
+}
+
+// vanilla's messages for clients older or newer than every version we support
+fn version_rejection(protocol: u32) -> TextComponent {
+    let (oldest, newest) = (ProtocolVersion::SUPPORTED[0], ProtocolVersion::SUPPORTED[ProtocolVersion::SUPPORTED.len() - 1]);
+    let key = if protocol > newest.protocol() { "multiplayer.disconnect.outdated_server" } else { "multiplayer.disconnect.outdated_client" };
+    TextComponent::translate_with(key, vec![TextComponent::text(format!("{}-{}", oldest.name(), newest.name()))])
+}
+
+async fn authenticate<V: SessionVerifier>(
+    verifier: &V,
+    connection: &mut Connection,
+    pending_login: PendingLogin,
+    encryption_response: EncryptionResponse
+) -> Result<LoginSuccess> {
+    if SERVER_KEY.decrypt(&encryption_response.verify_token)? != pending_login.verify_token {
+        return Err(ProtocolError::VerifyTokenMismatch);
+    }
+    let shared_secret = SERVER_KEY.decrypt_shared_secret(&encryption_response.shared_secret)?;
+    connection.enable_encryption(shared_secret).await?;
+    let hash = server_hash("", &shared_secret, SERVER_KEY.public_key_der());
+    let Some(profile) = verifier.has_joined(&pending_login.username, &hash).await? else {
+        return Err(ProtocolError::AuthenticationFailed { username: pending_login.username });
+    };
+    Ok(LoginSuccess { uuid: profile.id, username: profile.name, properties: profile.properties, strict_error_handling: false })
+}
+
+async fn finish_login(connection: &mut Connection, login_success: LoginSuccess) -> Result<()> {
+    if config().compression_threshold >= 0 {
+        connection.set_compression(config().compression_threshold).await?;
+    }
+    connection.sender().send(&login_success).await
+}
+
+async fn join_game(sender: &PacketSender) -> Result<()> {
+    let login_play = LoginPlay {
+        entity_id: 0,
+        is_hardcore: false,
+        dimension_names: vec!["minecraft:overworld".to_string()],
+        max_players: config().max_players,
+        view_distance: config().view_distance,
+        simulation_distance: config().simulation_distance,
+        reduced_debug_info: false,
+        enable_respawn_screen: false,
+        do_limited_crafting: false,
+        dimension_type: "minecraft:overworld".to_string(),
+        dimension_name: "minecraft:overworld".to_string(),
+        hashed_seed: 0,
+        game_mode: 3,
+        previous_game_mode: -1,
+        is_debug: false,
+        is_flat: false,
+        death_location: None,
+        portal_cooldown: 0,
+        // overworld is the first dimension type in REGISTRY, datapacks can only replace it
+        dimension_type_id: 0,
+        enforces_secure_chat: false
+    };
+    sender.send(&login_play).await?;
+    let spawn = config().spawn;
+    let (block_x, block_y, block_z) = (spawn.x.floor() as i32, spawn.y.floor() as i32, spawn.z.floor() as i32);
+    let (center_x, center_z) = (block_x >> 4, block_z >> 4);
+    sender.send(&SetCenterChunk { chunk_x: center_x, chunk_z: center_z }).await?;
+    // start waiting for level chunks
+    sender.send(&GameEvent { event: 13, value: 0.0 }).await?;
+
+    // send chunks in view distance
+    let radius = config().view_distance as i32;
+    for x in center_x - radius..=center_x + radius {
+        for z in center_z - radius..=center_z + radius {
+            let chunk = ChunkDataAndUpdateLight {
+                chunk_x: x,
+                chunk_z: z,
+                heightmaps: HEIGHT_MAP.deref().clone(),
+                data: empty_chunk_sections(384 / 16),
+                block_entities: vec![],
+                sky_light_mask: vec![],
+                block_light_mask: vec![],
+                empty_sky_light_mask: vec![],
+                empty_block_light_mask: vec![],
+                sky_light_arrays: vec![],
+                block_light_arrays: vec![]
+            };
+            sender.send(&chunk).await?;
+        }
+    }
+
+    // teleport player
+    let teleport = SynchronizePlayerPosition {
+        x: spawn.x,
+        y: spawn.y,
+        z: spawn.z,
+        yaw: spawn.yaw,
+        pitch: spawn.pitch,
+        flags: 0,
+        teleport_id: 0
+    };
+    sender.send(&teleport).await?;
+
+    // blocks are placed relative to the spawn block
+    let block = |x: i32, y: i32, z: i32, block_id: u32| BlockUpdate { x: block_x + x, y: (block_y + y) as i16, z: block_z + z, block_id };
+    sender.send(&block(0, 0, 0, 1)).await?;
+    for x in -4..=4 {
+        for z in -4..=4 {
+            sender.send(&block(x, 2, z, 7406)).await?;
+            sender.send(&block(x, 3, z, 1)).await?;
+        }
+    }
+
+    // send armorstand with player's entity id
+    let armor_stand = SpawnEntity {
+        entity_id: 1,
+        entity_uuid: Uuid::new_v4(),
+        entity_type: 2,
+        x: spawn.x,
+        y: spawn.y,
+        z: spawn.z,
+        pitch: -64,
+        yaw: Angle::from_degrees(spawn.yaw).0 as i8,
+        head_yaw: Angle::from_degrees(spawn.yaw).0 as i8,
+        data: 0,
+        velocity_x: 0,
+        velocity_y: 0,
+        velocity_z: 0
+    };
+    sender.send(&armor_stand).await?;
+
+    // make it invisible
+    let metadata = SetEntityMetadata {
+        entity_id: 1,
+        metadata: vec![EntityMetadata { index: 0, value: MetadataValue::Byte(0x20) }]
+    };
+    sender.send(&metadata).await?;
+
+    sender.send(&SetCamera { camera_id: 1 }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tokio::io::AsyncReadExt;
+    use minecraft_protocol::codec::MinecraftWriteTypes;
+    use minecraft_protocol::packets::encode_packet;
+    use minecraft_protocol::packets::handshaking::Handshake;
+    use minecraft_protocol::packets::login::LoginAcknowledged;
+    use minecraft_protocol::packets::status::StatusRequest;
+
+    fn frame(packet: ByteBuffer) -> Vec<u8> {
+        let mut frame = ByteBuffer::new();
+        frame.write_var_int(packet.len() as u32);
+        frame.write_bytes(packet.as_bytes());
+        frame.into_vec()
+    }
+
+    // a client connected to handle_connection with the default config
+    async fn connect() -> TcpStream {
+        CONFIG.get_or_init(ServerConfig::default);
+        CONFIGURATION.get_or_init(ConfigurationStage::new);
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let client = TcpStream::connect(listener.local_addr().unwrap()).await.unwrap();
+        let (socket, address) = listener.accept().await.unwrap();
+        tokio::spawn(handle_connection(socket, address, ServerState::new()));
+        client
+    }
+
+    #[tokio::test]
+    async fn disconnects_premature_login_acknowledged() {
+        let mut client = connect().await;
+        let version = ProtocolVersion::V1_20_4;
+        let handshake = Handshake { protocol_version: version.protocol(), server_address: "localhost".to_string(), server_port: 25565, next_state: 2 };
+        client.write_all(&frame(encode_packet(version, &handshake).unwrap())).await.unwrap();
+        client.write_all(&frame(encode_packet(version, &LoginAcknowledged).unwrap())).await.unwrap();
+        // nothing is sent back, the connection is just closed
+        let mut rest = Vec::new();
+        tokio::time::timeout(Duration::from_secs(5), client.read_to_end(&mut rest)).await.unwrap().unwrap();
+        assert!(rest.is_empty());
+    }
+
+    #[tokio::test]
+    async fn handshake_starting_like_a_legacy_ping() {
+        let mut client = connect().await;
+        let version = ProtocolVersion::V1_20_4;
+        let handshake = Handshake { protocol_version: version.protocol(), server_address: "a".repeat(246), server_port: 25565, next_state: 1 };
+        let handshake = frame(encode_packet(version, &handshake).unwrap());
+        // a length of 254 is the VarInt `0xfe 0x01`, the same bytes a 1.4 ping starts with
+        assert_eq!(handshake[..2], [0xfe, 0x01]);
+        client.write_all(&handshake).await.unwrap();
+        client.write_all(&frame(encode_packet(version, &StatusRequest).unwrap())).await.unwrap();
+
+        let mut response = [0; 3];
+        tokio::time::timeout(Duration::from_secs(5), client.read_exact(&mut response)).await.unwrap().unwrap();
+        // a Status Response frame, the legacy kick packet would start with 0xff
+        let mut response = ByteBuffer::from_bytes(&response);
+        response.read_var_int().unwrap();
+        assert_eq!(response.read_var_int().unwrap(), 0x00);
+    }
+}
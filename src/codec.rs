@@ -0,0 +1,652 @@
+use fastnbt::Value;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use uuid::Uuid;
+
+pub use bytebuffer::ByteBuffer;
+pub use minecraft_protocol_derive::{McDecode, McEncode};
+
+use crate::error::{ProtocolError, Result};
+use crate::nbt;
+use crate::types::{Angle, BitSet, FixedBitSet, Identifier, Position, Slot};
+
+const SEG_BITS: u32 = 0x7F;
+const CON_BIT: u32 = 0x80;
+pub const MAX_STRING_LENGTH: usize = 32767;
+
+pub trait MinecraftReadTypes {
+    fn read_var_int(&mut self) -> Result<u32>;
+
+    fn read_var_string(&mut self) -> Result<String>;
+
+    fn readabe_bytes(&self) -> usize;
+
+    fn read_uuid(&mut self) -> Result<Uuid>;
+
+    fn read_signed_var_int(&mut self) -> Result<i32>;
+
+    fn read_var_long(&mut self) -> Result<i64>;
+
+    fn read_position(&mut self) -> Result<Position>;
+
+    fn read_angle(&mut self) -> Result<Angle>;
+
+    fn read_identifier(&mut self) -> Result<Identifier>;
+
+    fn read_bit_set(&mut self) -> Result<BitSet>;
+
+    fn read_fixed_bit_set(&mut self, size: usize) -> Result<FixedBitSet>;
+
+    fn read_optional<T: McDecode>(&mut self) -> Result<Option<T>>;
+
+    fn read_length_prefixed<T: McDecode>(&mut self) -> Result<Vec<T>>;
+
+    fn read_byte_array(&mut self) -> Result<Vec<u8>>;
+
+    fn read_slot(&mut self) -> Result<Slot>;
+
+    fn read_compound(&mut self) -> Result<Value>;
+
+    fn read_nbt<T: DeserializeOwned>(&mut self) -> Result<T>;
+}
+
+pub trait MinecraftWriteTypes {
+
+    fn write_var_int(&mut self, int: u32);
+
+    fn write_var_string(&mut self, str: &str);
+
+    fn write_uuid(&mut self, uuid: &Uuid);
+
+    fn write_compound(&mut self, nbt: &Value) -> Result<()>;
+
+    fn write_signed_var_int(&mut self, int: i32);
+
+    fn write_var_long(&mut self, long: i64);
+
+    fn write_position(&mut self, position: &Position);
+
+    fn write_angle(&mut self, angle: Angle);
+
+    fn write_identifier(&mut self, identifier: &Identifier);
+
+    fn write_bit_set(&mut self, bit_set: &BitSet);
+
+    fn write_fixed_bit_set(&mut self, bit_set: &FixedBitSet);
+
+    fn write_optional<T: McEncode>(&mut self, value: &Option<T>) -> Result<()>;
+
+    fn write_length_prefixed<T: McEncode>(&mut self, elements: &[T]) -> Result<()>;
+
+    fn write_byte_array(&mut self, bytes: &[u8]);
+
+    fn write_slot(&mut self, slot: &Slot) -> Result<()>;
+
+    fn write_nbt<T: Serialize>(&mut self, value: &T) -> Result<()>;
+}
+
+impl MinecraftWriteTypes for ByteBuffer {
+    fn write_var_int(&mut self, mut int: u32) {
+        loop {
+            if (int & !SEG_BITS) == 0 {
+                self.write_u8(int as u8);
+                return;
+            }
+            self.write_u8(((int & SEG_BITS) | CON_BIT) as u8);
+            int >>= 7;
+        }
+    }
+
+    fn write_var_string(&mut self, str: &str) {
+        self.write_var_int(str.len() as u32);
+        self.write_bytes(str.as_bytes());
+    }
+
+    fn write_uuid(&mut self, uuid: &Uuid) {
+        let (most_sig_bits, least_sig_bits) = uuid.as_u64_pair();
+        self.write_u64(most_sig_bits);
+        self.write_u64(least_sig_bits);
+    }
+
+    fn write_compound(&mut self, nbt: &Value) -> Result<()> {
+        nbt::write_tag(self, nbt)
+    }
+
+    fn write_signed_var_int(&mut self, int: i32) {
+        self.write_var_int(int as u32);
+    }
+
+    fn write_var_long(&mut self, long: i64) {
+        let mut long = long as u64;
+        loop {
+            if (long & !(SEG_BITS as u64)) == 0 {
+                self.write_u8(long as u8);
+                return;
+            }
+            self.write_u8(((long & SEG_BITS as u64) | CON_BIT as u64) as u8);
+            long >>= 7;
+        }
+    }
+
+    fn write_position(&mut self, position: &Position) {
+        self.write_i64(position.to_long());
+    }
+
+    fn write_angle(&mut self, angle: Angle) {
+        self.write_u8(angle.0);
+    }
+
+    fn write_identifier(&mut self, identifier: &Identifier) {
+        self.write_var_string(&identifier.to_string());
+    }
+
+    fn write_bit_set(&mut self, bit_set: &BitSet) {
+        self.write_var_int(bit_set.0.len() as u32);
+        for word in &bit_set.0 {
+            self.write_u64(*word);
+        }
+    }
+
+    fn write_fixed_bit_set(&mut self, bit_set: &FixedBitSet) {
+        self.write_bytes(bit_set.as_bytes());
+    }
+
+    fn write_optional<T: McEncode>(&mut self, value: &Option<T>) -> Result<()> {
+        self.write_u8(value.is_some() as u8);
+        match value {
+            Some(value) => value.encode(self),
+            None => Ok(())
+        }
+    }
+
+    fn write_length_prefixed<T: McEncode>(&mut self, elements: &[T]) -> Result<()> {
+        self.write_var_int(elements.len() as u32);
+        for element in elements {
+            element.encode(self)?;
+        }
+        Ok(())
+    }
+
+    fn write_byte_array(&mut self, bytes: &[u8]) {
+        self.write_var_int(bytes.len() as u32);
+        self.write_bytes(bytes);
+    }
+
+    // an item without nbt is sent with a single end tag
+    fn write_slot(&mut self, slot: &Slot) -> Result<()> {
+        let Slot::Item { item_id, count, nbt } = slot else {
+            self.write_u8(0);
+            return Ok(());
+        };
+        self.write_u8(1);
+        self.write_signed_var_int(*item_id);
+        self.write_i8(*count);
+        match nbt {
+            Some(nbt) => self.write_compound(nbt),
+            None => {
+                self.write_u8(0);
+                Ok(())
+            }
+        }
+    }
+
+    fn write_nbt<T: Serialize>(&mut self, value: &T) -> Result<()> {
+        nbt::write(self, value)
+    }
+}
+
+impl MinecraftReadTypes for ByteBuffer {
+
+    fn read_var_int(&mut self) -> Result<u32> {
+        let mut value: u32 = 0;
+        let mut position: u8 = 0;
+        let mut current_byte: u8;
+        loop {
+            current_byte = self.read_u8()?;
+            value |= (current_byte as u32 & SEG_BITS) << position;
+            if (current_byte as u32 & CON_BIT) == 0 {
+                break;
+            }
+            position += 7;
+            if position >= 32 {
+                return Err(ProtocolError::VarIntTooLong);
+            }
+        }
+        Ok(value)
+    }
+
+    fn read_var_string(&mut self) -> Result<String> {
+        // the limit counts utf-16 code units, each of which takes at most 3 bytes in utf-8
+        let size = self.read_var_int()? as usize;
+        if size > MAX_STRING_LENGTH * 3 {
+            return Err(ProtocolError::StringTooLong { length: size, max: MAX_STRING_LENGTH * 3 });
+        }
+        let string_result = String::from_utf8(self.read_bytes(size)?)?;
+        let length = string_result.encode_utf16().count();
+        if length > MAX_STRING_LENGTH {
+            return Err(ProtocolError::StringTooLong { length, max: MAX_STRING_LENGTH });
+        }
+        Ok(string_result)
+    }
+
+    fn readabe_bytes(&self) -> usize {
+        self.get_wpos() - self.get_rpos()
+    }
+
+    fn read_uuid(&mut self) -> Result<Uuid> {
+        let most_sig_bits = self.read_u64()?;
+        let least_sig_bits = self.read_u64()?;
+        Ok(Uuid::from_u64_pair(most_sig_bits, least_sig_bits))
+    }
+
+    fn read_signed_var_int(&mut self) -> Result<i32> {
+        Ok(self.read_var_int()? as i32)
+    }
+
+    fn read_var_long(&mut self) -> Result<i64> {
+        let mut value: u64 = 0;
+        let mut position: u8 = 0;
+        loop {
+            let current_byte = self.read_u8()?;
+            value |= (current_byte as u64 & SEG_BITS as u64) << position;
+            if (current_byte as u32 & CON_BIT) == 0 {
+                break;
+            }
+            position += 7;
+            if position >= 64 {
+                return Err(ProtocolError::VarLongTooLong);
+            }
+        }
+        Ok(value as i64)
+    }
+
+    fn read_position(&mut self) -> Result<Position> {
+        Ok(Position::from_long(self.read_i64()?))
+    }
+
+    fn read_angle(&mut self) -> Result<Angle> {
+        Ok(Angle(self.read_u8()?))
+    }
+
+    fn read_identifier(&mut self) -> Result<Identifier> {
+        Identifier::parse(&self.read_var_string()?)
+    }
+
+    fn read_bit_set(&mut self) -> Result<BitSet> {
+        let length = self.read_var_int()? as usize;
+        check_array_length(length.saturating_mul(8), self.readabe_bytes())?;
+        let mut words = Vec::with_capacity(length);
+        for _ in 0..length {
+            words.push(self.read_u64()?);
+        }
+        Ok(BitSet(words))
+    }
+
+    fn read_fixed_bit_set(&mut self, size: usize) -> Result<FixedBitSet> {
+        Ok(FixedBitSet::from_bytes(size, self.read_bytes(size.div_ceil(8))?))
+    }
+
+    fn read_optional<T: McDecode>(&mut self) -> Result<Option<T>> {
+        if bool::decode(self)? {
+            Ok(Some(T::decode(self)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    // every element takes at least a byte, so the length can't be more than what's left of the packet
+    fn read_length_prefixed<T: McDecode>(&mut self) -> Result<Vec<T>> {
+        let length = self.read_var_int()? as usize;
+        check_array_length(length, self.readabe_bytes())?;
+        let mut elements = Vec::with_capacity(length);
+        for _ in 0..length {
+            elements.push(T::decode(self)?);
+        }
+        Ok(elements)
+    }
+
+    fn read_byte_array(&mut self) -> Result<Vec<u8>> {
+        let length = self.read_var_int()? as usize;
+        check_array_length(length, self.readabe_bytes())?;
+        Ok(self.read_bytes(length)?)
+    }
+
+    fn read_slot(&mut self) -> Result<Slot> {
+        if !bool::decode(self)? {
+            return Ok(Slot::Empty);
+        }
+        let item_id = self.read_signed_var_int()?;
+        let count = self.read_i8()?;
+        let nbt = match nbt::read_tag(self)? {
+            None => None,
+            Some(nbt @ Value::Compound(_)) => Some(nbt),
+            Some(_) => return Err(ProtocolError::InvalidNbt("item nbt isn't a compound".to_string()))
+        };
+        Ok(Slot::Item { item_id, count, nbt })
+    }
+
+    fn read_compound(&mut self) -> Result<Value> {
+        nbt::read_compound(self)
+    }
+
+    fn read_nbt<T: DeserializeOwned>(&mut self) -> Result<T> {
+        nbt::read(self)
+    }
+}
+
+// every element takes at least a byte, so a length past the remaining bytes can't be valid
+pub fn check_array_length(length: usize, remaining: usize) -> Result<()> {
+    if length > remaining {
+        return Err(ProtocolError::ArrayTooLong { length, max: remaining });
+    }
+    Ok(())
+}
+
+// wire format of a whole value, usually derived with #[derive(McEncode, McDecode)]
+// only fails for values the format can't hold, like nbt strings longer than 65535 bytes
+pub trait McEncode {
+    fn encode(&self, buffer: &mut ByteBuffer) -> Result<()>;
+}
+
+pub trait McDecode: Sized {
+    fn decode(buffer: &mut ByteBuffer) -> Result<Self>;
+}
+
+macro_rules! impl_primitive {
+    ($($ty:ty => $write:ident, $read:ident);* $(;)?) => {
+        $(
+            impl McEncode for $ty {
+                fn encode(&self, buffer: &mut ByteBuffer) -> Result<()> {
+                    buffer.$write(*self);
+                    Ok(())
+                }
+            }
+
+            impl McDecode for $ty {
+                fn decode(buffer: &mut ByteBuffer) -> Result<Self> {
+                    Ok(buffer.$read()?)
+                }
+            }
+        )*
+    };
+}
+
+impl_primitive! {
+    u8 => write_u8, read_u8;
+    i8 => write_i8, read_i8;
+    u16 => write_u16, read_u16;
+    i16 => write_i16, read_i16;
+    u32 => write_u32, read_u32;
+    i32 => write_i32, read_i32;
+    u64 => write_u64, read_u64;
+    i64 => write_i64, read_i64;
+    f32 => write_f32, read_f32;
+    f64 => write_f64, read_f64;
+}
+
+impl McEncode for bool {
+    fn encode(&self, buffer: &mut ByteBuffer) -> Result<()> {
+        buffer.write_u8(*self as u8);
+        Ok(())
+    }
+}
+
+impl McDecode for bool {
+    fn decode(buffer: &mut ByteBuffer) -> Result<Self> {
+        match buffer.read_u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            other => Err(ProtocolError::InvalidBoolean(other))
+        }
+    }
+}
+
+impl McEncode for String {
+    fn encode(&self, buffer: &mut ByteBuffer) -> Result<()> {
+        buffer.write_var_string(self);
+        Ok(())
+    }
+}
+
+impl McDecode for String {
+    fn decode(buffer: &mut ByteBuffer) -> Result<Self> {
+        buffer.read_var_string()
+    }
+}
+
+impl McEncode for Uuid {
+    fn encode(&self, buffer: &mut ByteBuffer) -> Result<()> {
+        buffer.write_uuid(self);
+        Ok(())
+    }
+}
+
+impl McDecode for Uuid {
+    fn decode(buffer: &mut ByteBuffer) -> Result<Self> {
+        buffer.read_uuid()
+    }
+}
+
+impl McEncode for Value {
+    fn encode(&self, buffer: &mut ByteBuffer) -> Result<()> {
+        buffer.write_compound(self)
+    }
+}
+
+impl McDecode for Value {
+    fn decode(buffer: &mut ByteBuffer) -> Result<Self> {
+        buffer.read_compound()
+    }
+}
+
+// a Vec without #[mc(length_prefixed)] takes up the rest of the packet
+impl<T: McEncode> McEncode for Vec<T> {
+    fn encode(&self, buffer: &mut ByteBuffer) -> Result<()> {
+        for element in self {
+            element.encode(buffer)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: McDecode> McDecode for Vec<T> {
+    fn decode(buffer: &mut ByteBuffer) -> Result<Self> {
+        let mut elements = Vec::new();
+        while buffer.readabe_bytes() > 0 {
+            elements.push(T::decode(buffer)?);
+        }
+        Ok(elements)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Debug;
+    use super::*;
+
+    fn round_trip<T: McEncode + McDecode + PartialEq + Debug>(value: T) -> Vec<u8> {
+        let mut buffer = ByteBuffer::new();
+        value.encode(&mut buffer).unwrap();
+        let bytes = buffer.as_bytes().to_vec();
+        assert_eq!(T::decode(&mut buffer).unwrap(), value);
+        assert_eq!(buffer.readabe_bytes(), 0);
+        bytes
+    }
+
+    #[derive(Debug, PartialEq, McEncode, McDecode)]
+    struct Attributes {
+        #[mc(varint)]
+        signed: i32,
+        #[mc(length_prefixed)]
+        names: Vec<String>,
+        #[mc(length_prefixed, varint)]
+        ids: Vec<u32>,
+        #[mc(optional)]
+        label: Option<String>,
+        #[mc(optional, varint)]
+        count: Option<u32>,
+        #[mc(skip)]
+        cached: bool,
+        rest: Vec<u8>
+    }
+
+    #[derive(Debug, PartialEq, McEncode, McDecode)]
+    enum Shape {
+        Empty,
+        Circle(f32),
+        #[mc(id = 5)]
+        Rectangle { width: u16, height: u16 },
+        Named(#[mc(length_prefixed)] Vec<u8>)
+    }
+
+    #[test]
+    fn derive_field_attributes() {
+        let bytes = round_trip(Attributes {
+            signed: -1,
+            names: vec!["a".to_string(), "bc".to_string()],
+            ids: vec![300],
+            label: None,
+            count: Some(1),
+            cached: false,
+            rest: vec![1, 2, 3]
+        });
+        assert_eq!(bytes, [
+            0xff, 0xff, 0xff, 0xff, 0x0f,
+            2, 1, b'a', 2, b'b', b'c',
+            1, 0xac, 0x02,
+            0,
+            1, 1,
+            1, 2, 3
+        ]);
+
+        // a huge length prefix fails instead of reserving memory for it
+        let mut buffer = ByteBuffer::from_bytes(&[0, 0xff, 0xff, 0xff, 0xff, 0x07, 1, b'a']);
+        assert!(matches!(Attributes::decode(&mut buffer), Err(ProtocolError::ArrayTooLong { length: 0x7fffffff, max: 2 })));
+    }
+
+    #[test]
+    fn derive_enum_variant_ids() {
+        assert_eq!(round_trip(Shape::Empty), [0]);
+        assert_eq!(round_trip(Shape::Circle(1.0)), [1, 0x3f, 0x80, 0, 0]);
+        assert_eq!(round_trip(Shape::Rectangle { width: 2, height: 3 }), [5, 0, 2, 0, 3]);
+        assert_eq!(round_trip(Shape::Named(vec![7])), [6, 1, 7]);
+        assert!(matches!(
+            Shape::decode(&mut ByteBuffer::from_bytes(&[2])),
+            Err(ProtocolError::UnknownVariant { name: "Shape", id: 2 })
+        ));
+    }
+
+    #[test]
+    fn signed_var_ints() {
+        let cases: [(i32, &[u8]); 5] = [
+            (0, &[0x00]),
+            (300, &[0xac, 0x02]),
+            (-1, &[0xff, 0xff, 0xff, 0xff, 0x0f]),
+            (i32::MAX, &[0xff, 0xff, 0xff, 0xff, 0x07]),
+            (i32::MIN, &[0x80, 0x80, 0x80, 0x80, 0x08])
+        ];
+        for (value, bytes) in cases {
+            let mut buffer = ByteBuffer::new();
+            buffer.write_signed_var_int(value);
+            assert_eq!(buffer.as_bytes(), bytes);
+            assert_eq!(buffer.read_signed_var_int().unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn var_longs() {
+        let cases: [(i64, &[u8]); 5] = [
+            (0, &[0x00]),
+            (2147483648, &[0x80, 0x80, 0x80, 0x80, 0x08]),
+            (i64::MAX, &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f]),
+            (-1, &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01]),
+            (i64::MIN, &[0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x01])
+        ];
+        for (value, bytes) in cases {
+            let mut buffer = ByteBuffer::new();
+            buffer.write_var_long(value);
+            assert_eq!(buffer.as_bytes(), bytes);
+            assert_eq!(buffer.read_var_long().unwrap(), value);
+        }
+        let mut buffer = ByteBuffer::from_bytes(&[0xff; 11]);
+        assert!(matches!(buffer.read_var_long(), Err(ProtocolError::VarLongTooLong)));
+    }
+
+    #[test]
+    fn positions_and_angles() {
+        // example from the protocol documentation
+        let position = Position::new(18357644, 831, -20882616);
+        assert_eq!(position.to_long(), 0x4607632c15b4833f);
+        for position in [position, Position::new(-33554432, -2048, 33554431), Position::new(-1, -1, -1)] {
+            assert_eq!(round_trip(position).len(), 8);
+        }
+        assert_eq!(round_trip(Angle::from_degrees(90.0)), [64]);
+        assert_eq!(Angle::from_degrees(-90.0), Angle(192));
+        assert_eq!(Angle(128).degrees(), 180.0);
+    }
+
+    #[test]
+    fn identifiers_and_bit_sets() {
+        let bytes = round_trip(Identifier::parse("stone").unwrap());
+        assert_eq!(&bytes[1..], b"minecraft:stone");
+        round_trip(Identifier::new("my_pack", "worldgen/biome.json").unwrap());
+        let mut buffer = ByteBuffer::new();
+        buffer.write_var_string("Minecraft:Stone");
+        assert!(matches!(buffer.read_identifier(), Err(ProtocolError::InvalidIdentifier(_))));
+
+        let mut bit_set = BitSet::new();
+        bit_set.set(70, true);
+        assert_eq!(round_trip(bit_set).len(), 1 + 2 * 8);
+
+        let mut fixed = FixedBitSet::new(20);
+        fixed.set(19, true);
+        let mut buffer = ByteBuffer::new();
+        buffer.write_fixed_bit_set(&fixed);
+        assert_eq!(buffer.as_bytes(), [0, 0, 0x08]);
+        assert_eq!(buffer.read_fixed_bit_set(20).unwrap(), fixed);
+    }
+
+    #[test]
+    fn optionals_and_arrays() {
+        let mut buffer = ByteBuffer::new();
+        buffer.write_optional(&Some(7u8)).unwrap();
+        buffer.write_optional::<u8>(&None).unwrap();
+        buffer.write_length_prefixed(&["a".to_string(), "b".to_string()]).unwrap();
+        buffer.write_byte_array(&[1, 2, 3]);
+        assert_eq!(buffer.as_bytes(), [1, 7, 0, 2, 1, b'a', 1, b'b', 3, 1, 2, 3]);
+        assert_eq!(buffer.read_optional::<u8>().unwrap(), Some(7));
+        assert_eq!(buffer.read_optional::<u8>().unwrap(), None);
+        assert_eq!(buffer.read_length_prefixed::<String>().unwrap(), ["a", "b"]);
+        assert_eq!(buffer.read_byte_array().unwrap(), [1, 2, 3]);
+
+        // lengths past the end of the packet are rejected before allocating
+        let mut buffer = ByteBuffer::from_bytes(&[0xff, 0xff, 0xff, 0xff, 0x07, 0]);
+        assert!(matches!(buffer.read_byte_array(), Err(ProtocolError::ArrayTooLong { length: 0x7fffffff, max: 1 })));
+        let mut buffer = ByteBuffer::from_bytes(&[2, 0, 0, 0, 0, 0, 0, 0, 0]);
+        assert!(matches!(buffer.read_bit_set(), Err(ProtocolError::ArrayTooLong { length: 16, max: 8 })));
+        let mut buffer = ByteBuffer::from_bytes(&[3, 1, 2]);
+        assert!(matches!(buffer.read_length_prefixed::<u8>(), Err(ProtocolError::ArrayTooLong { length: 3, max: 2 })));
+    }
+
+    #[test]
+    fn slots() {
+        assert_eq!(round_trip(Slot::Empty), [0]);
+        assert_eq!(round_trip(Slot::Item { item_id: 1, count: 64, nbt: None }), [1, 1, 64, 0]);
+        let nbt = Value::Compound([("Damage".to_string(), Value::Int(3))].into_iter().collect());
+        round_trip(Slot::Item { item_id: 800, count: 1, nbt: Some(nbt) });
+    }
+
+    #[test]
+    fn read_errors() {
+        let mut buffer = ByteBuffer::from_bytes(&[0xff, 0xff, 0xff, 0xff, 0xff, 0x01]);
+        assert!(matches!(buffer.read_var_int(), Err(ProtocolError::VarIntTooLong)));
+        let mut buffer = ByteBuffer::from_bytes(&[0x80]);
+        assert!(matches!(buffer.read_var_int(), Err(ProtocolError::UnexpectedEof)));
+        let mut buffer = ByteBuffer::from_bytes(&[2, 0xc3, 0x28]);
+        assert!(matches!(buffer.read_var_string(), Err(ProtocolError::InvalidUtf8(_))));
+        let mut buffer = ByteBuffer::new();
+        buffer.write_var_string(&"a".repeat(MAX_STRING_LENGTH + 1));
+        assert!(matches!(buffer.read_var_string(), Err(ProtocolError::StringTooLong { .. })));
+        assert!(matches!(bool::decode(&mut ByteBuffer::from_bytes(&[2])), Err(ProtocolError::InvalidBoolean(2))));
+    }
+}
@@ -0,0 +1,248 @@
+use std::fmt::{Display, Formatter};
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
+
+use ipnet::IpNet;
+use serde::Deserialize;
+
+use crate::text::TextComponent;
+
+pub const DEFAULT_CONFIG_PATH: &str = "server.toml";
+pub const DEFAULT_FAVICON_PATH: &str = "server-icon.png";
+// the client refuses view distances outside of this range
+pub const MIN_DISTANCE: u32 = 2;
+pub const MAX_DISTANCE: u32 = 32;
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    pub listen: Vec<SocketAddr>,
+    pub motd: TextComponent,
+    // shown to clients in the debug screen
+    pub brand: String,
+    pub max_players: u32,
+    // keeps the online count in the server list but leaves out the player names
+    pub hide_player_list: bool,
+    pub view_distance: u32,
+    pub simulation_distance: u32,
+    // online mode encrypts the connection and checks the player with the session service before Login Success
+    pub online_mode: bool,
+    // packets of at least this many bytes get compressed after login, negative turns compression off
+    pub compression_threshold: i32,
+    // how a proxy in front of the server passes on the player's address, uuid and skin
+    pub forwarding: ForwardingMode,
+    // shared with the proxy, Velocity signs the forwarded data with it
+    pub forwarding_secret: String,
+    // expect a PROXY protocol header from a load balancer before the handshake
+    pub proxy_protocol: bool,
+    // networks the header is accepted from, connections from anywhere else are closed
+    pub trusted_proxies: Vec<IpNet>,
+    // 64x64 png shown in the server list, the server runs without one when the file is missing
+    pub favicon: PathBuf,
+    // directories in the vanilla datapack layout, loaded in order over the built in registries
+    pub datapacks: Vec<PathBuf>,
+    pub spawn: SpawnLocation
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SpawnLocation {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub yaw: f32,
+    pub pitch: f32
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ForwardingMode {
+    #[default]
+    None,
+    // legacy forwarding in the handshake's server address, anyone who can reach the server can fake it
+    BungeeCord,
+    // modern forwarding through a login plugin message, signed with `forwarding_secret`
+    Velocity
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io { path: PathBuf, error: std::io::Error },
+    Parse { path: PathBuf, error: toml::de::Error },
+    Invalid { field: &'static str, reason: String }
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        ServerConfig {
+            listen: vec![SocketAddr::from(([127, 0, 0, 1], 25565))],
+            motd: TextComponent::text("A Minecraft Server"),
+            brand: "rust-minecraft-protocol".to_string(),
+            max_players: 100,
+            hide_player_list: false,
+            view_distance: 10,
+            simulation_distance: 10,
+            online_mode: false,
+            compression_threshold: 256,
+            forwarding: ForwardingMode::None,
+            forwarding_secret: String::new(),
+            proxy_protocol: false,
+            trusted_proxies: vec![],
+            favicon: PathBuf::from(DEFAULT_FAVICON_PATH),
+            datapacks: vec![],
+            spawn: SpawnLocation::default()
+        }
+    }
+}
+
+impl Default for SpawnLocation {
+    fn default() -> Self {
+        SpawnLocation { x: 0.5, y: 1.0, z: 0.5, yaw: 0.0, pitch: 0.0 }
+    }
+}
+
+impl ServerConfig {
+    // missing keys keep their default value, unknown keys are rejected so typos don't go unnoticed
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .map_err(|error| ConfigError::Io { path: path.to_path_buf(), error })?;
+        let config: ServerConfig = toml::from_str(&contents)
+            .map_err(|error| ConfigError::Parse { path: path.to_path_buf(), error })?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.listen.is_empty() {
+            return invalid("listen", "needs at least one address".to_string());
+        }
+        if self.max_players > i32::MAX as u32 {
+            return invalid("max_players", format!("{} is more than {}", self.max_players, i32::MAX));
+        }
+        for (field, distance) in [("view_distance", self.view_distance), ("simulation_distance", self.simulation_distance)] {
+            if !(MIN_DISTANCE..=MAX_DISTANCE).contains(&distance) {
+                return invalid(field, format!("{distance} is not between {MIN_DISTANCE} and {MAX_DISTANCE}"));
+            }
+        }
+        if self.online_mode && self.forwarding != ForwardingMode::None {
+            return invalid("online_mode", "has to be off with forwarding, the proxy authenticates the players".to_string());
+        }
+        if self.forwarding == ForwardingMode::Velocity && self.forwarding_secret.is_empty() {
+            return invalid("forwarding_secret", "is required for velocity forwarding".to_string());
+        }
+        if self.proxy_protocol && self.trusted_proxies.is_empty() {
+            return invalid("trusted_proxies", "needs at least one network with proxy_protocol".to_string());
+        }
+        self.spawn.validate()
+    }
+
+    // IPv4 peers of a dual stack listener show up as mapped IPv6 addresses
+    pub fn is_trusted_proxy(&self, address: IpAddr) -> bool {
+        let address = address.to_canonical();
+        self.trusted_proxies.iter().any(|network| network.contains(&address))
+    }
+}
+
+impl SpawnLocation {
+    fn validate(&self) -> Result<(), ConfigError> {
+        let coordinates = [("spawn.x", self.x), ("spawn.y", self.y), ("spawn.z", self.z)];
+        let rotation = [("spawn.yaw", self.yaw as f64), ("spawn.pitch", self.pitch as f64)];
+        for (field, value) in coordinates.into_iter().chain(rotation) {
+            if !value.is_finite() {
+                return invalid(field, format!("{value} is not a finite number"));
+            }
+        }
+        // the world border stops at 29999984 blocks, the demo world spans y -64 to 320
+        for (field, value) in [("spawn.x", self.x), ("spawn.z", self.z)] {
+            if value.abs() > 29_999_984.0 {
+                return invalid(field, format!("{value} is outside of the world border"));
+            }
+        }
+        if !(-64.0..320.0).contains(&self.y) {
+            return invalid("spawn.y", format!("{} is not between -64 and 320", self.y));
+        }
+        if !(-90.0..=90.0).contains(&self.pitch) {
+            return invalid("spawn.pitch", format!("{} is not between -90 and 90", self.pitch));
+        }
+        Ok(())
+    }
+}
+
+fn invalid(field: &'static str, reason: String) -> Result<(), ConfigError> {
+    Err(ConfigError::Invalid { field, reason })
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::Io { path, error } => write!(f, "failed to read {}: {error}", path.display()),
+            ConfigError::Parse { path, error } => write!(f, "failed to parse {}: {error}", path.display()),
+            ConfigError::Invalid { field, reason } => write!(f, "invalid {field}: {reason}")
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::Io { error, .. } => Some(error),
+            ConfigError::Parse { error, .. } => Some(error),
+            ConfigError::Invalid { .. } => None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(contents: &str) -> Result<ServerConfig, String> {
+        let config: ServerConfig = toml::from_str(contents).map_err(|e| e.to_string())?;
+        config.validate().map_err(|e| e.to_string())?;
+        Ok(config)
+    }
+
+    #[test]
+    fn defaults_and_overrides() {
+        assert_eq!(parse("").unwrap(), ServerConfig::default());
+        let config = parse(r#"
+            listen = ["0.0.0.0:25565", "[::]:25566"]
+            motd = { text = "hello", color = "gold" }
+            max_players = 20
+            compression_threshold = -1
+
+            [spawn]
+            y = 64.0
+        "#).unwrap();
+        assert_eq!(config.listen.len(), 2);
+        assert_eq!(config.motd, TextComponent::text("hello").color("gold"));
+        assert_eq!(config.max_players, 20);
+        assert_eq!(config.compression_threshold, -1);
+        assert_eq!(parse(r#"forwarding = "bungeecord""#).unwrap().forwarding, ForwardingMode::BungeeCord);
+        assert_eq!(config.spawn, SpawnLocation { y: 64.0, ..SpawnLocation::default() });
+        let config = parse("proxy_protocol = true\ntrusted_proxies = [\"10.0.0.0/8\", \"::1/128\"]").unwrap();
+        assert!(config.is_trusted_proxy("10.1.2.3".parse().unwrap()));
+        assert!(config.is_trusted_proxy("::ffff:10.1.2.3".parse().unwrap()));
+        assert!(!config.is_trusted_proxy("192.0.2.1".parse().unwrap()));
+        assert_eq!(parse(r#"motd = "plain""#).unwrap().motd, TextComponent::text("plain"));
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        assert_eq!(parse("view_distance = 64").unwrap_err(), "invalid view_distance: 64 is not between 2 and 32");
+        assert_eq!(parse("listen = []").unwrap_err(), "invalid listen: needs at least one address");
+        assert_eq!(parse("spawn = { pitch = 91.0 }").unwrap_err(), "invalid spawn.pitch: 91 is not between -90 and 90");
+        assert_eq!(
+            parse("online_mode = true\nforwarding = \"bungeecord\"").unwrap_err(),
+            "invalid online_mode: has to be off with forwarding, the proxy authenticates the players"
+        );
+        assert_eq!(parse(r#"forwarding = "velocity""#).unwrap_err(), "invalid forwarding_secret: is required for velocity forwarding");
+        assert_eq!(
+            parse("proxy_protocol = true").unwrap_err(),
+            "invalid trusted_proxies: needs at least one network with proxy_protocol"
+        );
+        assert!(parse("max_player = 1").unwrap_err().contains("unknown field `max_player`"));
+        assert!(parse(r#"listen = ["localhost"]"#).is_err());
+    }
+}
@@ -0,0 +1,210 @@
+use crate::codec::{ByteBuffer, MinecraftReadTypes, MinecraftWriteTypes};
+use crate::error::{ProtocolError, Result};
+use crate::packets::configuration::{
+    ClientInformation, ClientboundConfigurationPacket, ClientboundKnownPacks, ClientboundPluginMessage, FeatureFlags, FinishConfiguration,
+    KnownPack, RegistryData, RegistryTags, ServerboundConfigurationPacket, UpdateTags
+};
+use crate::registry::{Registries, REGISTRY};
+use crate::types::Identifier;
+use crate::version::ProtocolVersion;
+
+pub const BRAND_CHANNEL: &str = "minecraft:brand";
+
+// what the client tells us about itself during configuration
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ClientDetails {
+    pub brand: Option<String>,
+    pub information: Option<ClientInformation>
+}
+
+// an extra step of the configuration phase, the client only moves on to play once every task is done
+pub trait ConfigurationTask: Send {
+    // queues the task's first packets, true when it doesn't wait for the client
+    fn start(&mut self, version: ProtocolVersion, outgoing: &mut Vec<ClientboundConfigurationPacket>) -> Result<bool>;
+
+    // sees every packet the client sends while the task runs, true once the task is done
+    fn handle(&mut self, packet: &ServerboundConfigurationPacket, outgoing: &mut Vec<ClientboundConfigurationPacket>) -> Result<bool>;
+}
+
+type TaskFactory = Box<dyn Fn() -> Box<dyn ConfigurationTask> + Send + Sync>;
+
+// what every client goes through between Login Acknowledged and play, shared by all connections
+pub struct ConfigurationStage {
+    pub brand: Option<String>,
+    pub feature_flags: Vec<Identifier>,
+    pub registries: Registries,
+    pub tags: Vec<RegistryTags>,
+    tasks: Vec<TaskFactory>
+}
+
+// the configuration phase of one connection
+pub struct Configuration {
+    client: ClientDetails,
+    tasks: Vec<Box<dyn ConfigurationTask>>
+}
+
+// asks 1.20.5+ clients which vanilla data packs they have, like vanilla does before sending the registries
+#[derive(Debug, Default)]
+pub struct KnownPacksTask;
+
+impl Default for ConfigurationStage {
+    fn default() -> Self {
+        ConfigurationStage {
+            brand: None,
+            feature_flags: vec![Identifier::minecraft("vanilla").unwrap()],
+            registries: REGISTRY.clone(),
+            tags: vec![],
+            tasks: vec![]
+        }
+    }
+}
+
+impl ConfigurationStage {
+    // the known packs exchange is the only task until plugins add their own
+    pub fn new() -> Self {
+        let mut stage = ConfigurationStage::default();
+        stage.add_task(|| KnownPacksTask);
+        stage
+    }
+
+    // `task` creates the task again for every connection
+    pub fn add_task<T: ConfigurationTask + 'static>(&mut self, task: impl Fn() -> T + Send + Sync + 'static) {
+        self.tasks.push(Box::new(move || Box::new(task())));
+    }
+
+    // queues everything sent after Login Acknowledged, Finish Configuration follows once every task is done
+    pub fn start(&self, version: ProtocolVersion, outgoing: &mut Vec<ClientboundConfigurationPacket>) -> Result<Configuration> {
+        if let Some(brand) = &self.brand {
+            let mut data = ByteBuffer::new();
+            data.write_var_string(brand);
+            outgoing.push(ClientboundPluginMessage { channel: BRAND_CHANNEL.to_string(), data: data.into_vec() }.into());
+        }
+        outgoing.push(FeatureFlags { feature_flags: self.feature_flags.clone() }.into());
+        let mut tasks = Vec::new();
+        for task in &self.tasks {
+            let mut task = task();
+            if !task.start(version, outgoing)? {
+                tasks.push(task);
+            }
+        }
+        if version >= ProtocolVersion::V1_20_6 {
+            outgoing.extend(self.registries.to_entries(version)?.into_iter().map(Into::into));
+        } else {
+            outgoing.push(RegistryData { registry_codec: self.registries.to_codec(version)? }.into());
+        }
+        outgoing.push(UpdateTags { registries: self.tags.clone() }.into());
+        let configuration = Configuration { client: ClientDetails::default(), tasks };
+        if configuration.is_done() {
+            outgoing.push(FinishConfiguration.into());
+        }
+        Ok(configuration)
+    }
+}
+
+impl Configuration {
+    pub fn client(&self) -> &ClientDetails {
+        &self.client
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    // records the client's details and passes the packet on to the running tasks
+    pub fn handle(&mut self, packet: &ServerboundConfigurationPacket, outgoing: &mut Vec<ClientboundConfigurationPacket>) -> Result<()> {
+        match packet {
+            ServerboundConfigurationPacket::ClientInformation(information) => self.client.information = Some(information.clone()),
+            ServerboundConfigurationPacket::ServerboundPluginMessage(message) if message.channel == BRAND_CHANNEL => {
+                self.client.brand = Some(ByteBuffer::from_bytes(&message.data).read_var_string()?);
+            }
+            // Finish Configuration is only sent once every task is done
+            ServerboundConfigurationPacket::AcknowledgeFinishConfiguration(_) if !self.is_done() => {
+                return Err(ProtocolError::UnfinishedConfiguration(self.tasks.len()));
+            }
+            _ => {}
+        }
+        if self.is_done() {
+            return Ok(());
+        }
+        let mut running = Vec::new();
+        for mut task in std::mem::take(&mut self.tasks) {
+            if !task.handle(packet, outgoing)? {
+                running.push(task);
+            }
+        }
+        self.tasks = running;
+        if self.is_done() {
+            outgoing.push(FinishConfiguration.into());
+        }
+        Ok(())
+    }
+}
+
+impl ConfigurationTask for KnownPacksTask {
+    fn start(&mut self, version: ProtocolVersion, outgoing: &mut Vec<ClientboundConfigurationPacket>) -> Result<bool> {
+        if version < ProtocolVersion::V1_20_6 {
+            return Ok(true);
+        }
+        let core = KnownPack { namespace: "minecraft".to_string(), id: "core".to_string(), version: version.name().to_string() };
+        outgoing.push(ClientboundKnownPacks { known_packs: vec![core] }.into());
+        Ok(false)
+    }
+
+    // the registries are sent with their data either way, so the answer itself doesn't matter
+    fn handle(&mut self, packet: &ServerboundConfigurationPacket, _outgoing: &mut Vec<ClientboundConfigurationPacket>) -> Result<bool> {
+        Ok(matches!(packet, ServerboundConfigurationPacket::ServerboundKnownPacks(_)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packets::configuration::{AcknowledgeFinishConfiguration, ServerboundKnownPacks, ServerboundPluginMessage};
+
+    // waits for a plugin message on its channel
+    struct HandshakeTask;
+
+    impl ConfigurationTask for HandshakeTask {
+        fn start(&mut self, _version: ProtocolVersion, outgoing: &mut Vec<ClientboundConfigurationPacket>) -> Result<bool> {
+            outgoing.push(ClientboundPluginMessage { channel: "example:hello".to_string(), data: vec![] }.into());
+            Ok(false)
+        }
+
+        fn handle(&mut self, packet: &ServerboundConfigurationPacket, _outgoing: &mut Vec<ClientboundConfigurationPacket>) -> Result<bool> {
+            Ok(matches!(packet, ServerboundConfigurationPacket::ServerboundPluginMessage(message) if message.channel == "example:hello"))
+        }
+    }
+
+    fn is_finish(packet: Option<&ClientboundConfigurationPacket>) -> bool {
+        matches!(packet, Some(ClientboundConfigurationPacket::FinishConfiguration(_)))
+    }
+
+    #[test]
+    fn records_the_client_and_finishes_after_the_tasks() {
+        let mut stage = ConfigurationStage::new();
+        stage.brand = Some("test".to_string());
+        let mut outgoing = Vec::new();
+        let mut configuration = stage.start(ProtocolVersion::V1_20_4, &mut outgoing).unwrap();
+        assert_eq!(outgoing[0], ClientboundPluginMessage { channel: BRAND_CHANNEL.to_string(), data: b"\x04test".to_vec() }.into());
+        assert!(is_finish(outgoing.last()));
+        let brand = ServerboundPluginMessage { channel: BRAND_CHANNEL.to_string(), data: b"\x07vanilla".to_vec() };
+        configuration.handle(&brand.into(), &mut outgoing).unwrap();
+        assert_eq!(configuration.client().brand.as_deref(), Some("vanilla"));
+
+        let mut stage = ConfigurationStage::new();
+        stage.add_task(|| HandshakeTask);
+        let mut outgoing = Vec::new();
+        let mut configuration = stage.start(ProtocolVersion::V1_20_6, &mut outgoing).unwrap();
+        assert!(outgoing.iter().any(|packet| matches!(packet, ClientboundConfigurationPacket::ClientboundKnownPacks(_))));
+        assert!(!is_finish(outgoing.last()));
+        assert!(configuration.handle(&AcknowledgeFinishConfiguration.into(), &mut outgoing).is_err());
+
+        let mut outgoing = Vec::new();
+        configuration.handle(&ServerboundKnownPacks { known_packs: vec![] }.into(), &mut outgoing).unwrap();
+        assert!(outgoing.is_empty());
+        let hello = ServerboundPluginMessage { channel: "example:hello".to_string(), data: vec![] };
+        configuration.handle(&hello.into(), &mut outgoing).unwrap();
+        assert!(configuration.is_done());
+        assert!(is_finish(outgoing.last()));
+    }
+}
@@ -0,0 +1,206 @@
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use bytebuffer::ByteBuffer;
+use futures::{SinkExt, StreamExt};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio::task::{JoinError, JoinHandle};
+use tokio::time::{interval_at, Instant, Interval, MissedTickBehavior};
+use tokio_util::codec::{FramedRead, FramedWrite};
+
+use crate::encryption::SHARED_SECRET_LENGTH;
+use crate::error::{ProtocolError, Result};
+use crate::forwarding::ForwardedPlayer;
+use crate::framing::MinecraftFrameCodec;
+use crate::packets::login::SetCompression;
+use crate::packets::{encode_packet, Packet};
+use crate::version::ProtocolVersion;
+
+pub const OUTGOING_QUEUE_SIZE: usize = 128;
+pub const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(10);
+
+// queued in order with the packets so the writer switches format exactly between two frames
+#[derive(Debug)]
+enum Outgoing {
+    Frame(ByteBuffer),
+    CompressionThreshold(Option<usize>),
+    Encryption([u8; SHARED_SECRET_LENGTH])
+}
+
+// cheap handle for queueing packets on a connection's writer task
+#[derive(Debug, Clone)]
+pub struct PacketSender {
+    outgoing: mpsc::Sender<Outgoing>,
+    // picked by the handshake, shared with every clone
+    version: Arc<OnceLock<ProtocolVersion>>
+}
+
+impl PacketSender {
+    async fn queue(&self, outgoing: Outgoing) -> Result<()> {
+        self.outgoing.send(outgoing).await
+            .map_err(|_| ProtocolError::ConnectionClosed)
+    }
+
+    // fails once the writer task has stopped
+    pub async fn send_raw(&self, buffer: ByteBuffer) -> Result<()> {
+        self.queue(Outgoing::Frame(buffer)).await
+    }
+
+    pub async fn send<P: Packet>(&self, packet: &P) -> Result<()> {
+        self.send_raw(encode_packet(self.version(), packet)?).await
+    }
+
+    // the default version until the handshake has set one
+    pub fn version(&self) -> ProtocolVersion {
+        self.version.get().copied().unwrap_or_default()
+    }
+}
+
+pub struct Connection {
+    reader: FramedRead<OwnedReadHalf, MinecraftFrameCodec>,
+    sender: PacketSender,
+    // finishes with the error that stopped the writer
+    writer: Option<JoinHandle<Result<()>>>,
+    peer_address: Option<SocketAddr>,
+    forwarded: Option<ForwardedPlayer>
+}
+
+impl Connection {
+    pub fn new(socket: TcpStream) -> Self {
+        let peer_address = socket.peer_addr().ok();
+        let (read_half, write_half) = socket.into_split();
+        let (outgoing, queue) = mpsc::channel(OUTGOING_QUEUE_SIZE);
+        let writer = tokio::spawn(write_loop(FramedWrite::new(write_half, MinecraftFrameCodec::new()), queue));
+        Connection {
+            reader: FramedRead::new(read_half, MinecraftFrameCodec::new()),
+            sender: PacketSender { outgoing, version: Arc::new(OnceLock::new()) },
+            writer: Some(writer),
+            peer_address,
+            forwarded: None
+        }
+    }
+
+    // waits for the next frame, None once the peer has closed the connection.
+    // Fails with the writer's error if writing stops first
+    pub async fn read_frame(&mut self) -> Option<Result<ByteBuffer>> {
+        match &mut self.writer {
+            Some(writer) => tokio::select! {
+                frame = self.reader.next() => frame,
+                result = writer => {
+                    self.writer = None;
+                    Some(Err(writer_error(result)))
+                }
+            },
+            None => self.reader.next().await
+        }
+    }
+
+    // why the writer stopped, for after sending failed with ConnectionClosed.
+    // Waits for the writer, so it must not be called while it's still running
+    pub async fn close_reason(&mut self) -> ProtocolError {
+        match self.writer.take() {
+            Some(writer) => writer_error(writer.await),
+            None => ProtocolError::ConnectionClosed
+        }
+    }
+
+    pub fn sender(&self) -> &PacketSender {
+        &self.sender
+    }
+
+    pub fn version(&self) -> ProtocolVersion {
+        self.sender.version()
+    }
+
+    // only the first call has an effect, the version is picked once by the handshake
+    pub fn set_version(&mut self, version: ProtocolVersion) {
+        let _ = self.sender.version.set(version);
+    }
+
+    // the directly connected peer, which is the proxy when forwarding is used
+    pub fn peer_address(&self) -> Option<SocketAddr> {
+        self.peer_address
+    }
+
+    // a PROXY protocol header replaces the load balancer's address with the client's
+    pub fn set_peer_address(&mut self, address: SocketAddr) {
+        self.peer_address = Some(address);
+    }
+
+    pub fn forwarded(&self) -> Option<&ForwardedPlayer> {
+        self.forwarded.as_ref()
+    }
+
+    pub fn set_forwarded(&mut self, forwarded: ForwardedPlayer) {
+        self.forwarded = Some(forwarded);
+    }
+
+    // the player's own address, as forwarded by the proxy if there is one
+    pub fn client_address(&self) -> Option<IpAddr> {
+        match &self.forwarded {
+            Some(forwarded) => Some(forwarded.address),
+            None => self.peer_address.map(|address| address.ip())
+        }
+    }
+
+    // sends Set Compression and switches both directions to the compressed format,
+    // a negative threshold turns compression off again. Only valid during login
+    pub async fn set_compression(&mut self, threshold: i32) -> Result<()> {
+        let threshold_bytes = usize::try_from(threshold).ok();
+        self.sender.send(&SetCompression { threshold }).await?;
+        self.sender.queue(Outgoing::CompressionThreshold(threshold_bytes)).await?;
+        self.reader.decoder_mut().set_compression_threshold(threshold_bytes);
+        Ok(())
+    }
+
+    // everything after the Encryption Response is encrypted in both directions
+    pub async fn enable_encryption(&mut self, shared_secret: [u8; SHARED_SECRET_LENGTH]) -> Result<()> {
+        self.sender.queue(Outgoing::Encryption(shared_secret)).await?;
+        self.reader.decoder_mut().enable_encryption(&shared_secret);
+        Ok(())
+    }
+}
+
+// drains the outgoing queue, flushing only once no more packets are immediately waiting.
+// Stops at the first error, which the connection hands to its reader
+async fn write_loop(mut writer: FramedWrite<OwnedWriteHalf, MinecraftFrameCodec>, mut queue: mpsc::Receiver<Outgoing>) -> Result<()> {
+    while let Some(outgoing) = queue.recv().await {
+        write_outgoing(&mut writer, outgoing).await?;
+        while let Ok(outgoing) = queue.try_recv() {
+            write_outgoing(&mut writer, outgoing).await?;
+        }
+        writer.flush().await?;
+    }
+    Ok(())
+}
+
+// a writer that panicked or ended without an error still leaves the connection closed
+fn writer_error(result: std::result::Result<Result<()>, JoinError>) -> ProtocolError {
+    match result {
+        Ok(Err(e)) => e,
+        _ => ProtocolError::ConnectionClosed
+    }
+}
+
+// frames are encoded as soon as they're fed, so a format change only affects the frames after it
+async fn write_outgoing(writer: &mut FramedWrite<OwnedWriteHalf, MinecraftFrameCodec>, outgoing: Outgoing) -> Result<()> {
+    match outgoing {
+        Outgoing::Frame(buffer) => writer.feed(buffer).await,
+        Outgoing::CompressionThreshold(threshold) => {
+            writer.encoder_mut().set_compression_threshold(threshold);
+            Ok(())
+        }
+        Outgoing::Encryption(shared_secret) => {
+            writer.encoder_mut().enable_encryption(&shared_secret);
+            Ok(())
+        }
+    }
+}
+
+pub fn keep_alive_interval() -> Interval {
+    let mut interval = interval_at(Instant::now() + KEEP_ALIVE_INTERVAL, KEEP_ALIVE_INTERVAL);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    interval
+}
@@ -0,0 +1,137 @@
+use aes::cipher::inout::InOutBuf;
+use aes::cipher::{BlockDecryptMut, BlockEncryptMut, KeyIvInit};
+use aes::Aes128;
+use rand::RngCore;
+use rsa::pkcs8::EncodePublicKey;
+use rsa::{Pkcs1v15Encrypt, RsaPrivateKey};
+use sha1::{Digest, Sha1};
+
+use crate::error::{ProtocolError, Result};
+
+// the vanilla server uses a 1024 bit key for the login handshake
+pub const SERVER_KEY_BITS: usize = 1024;
+pub const VERIFY_TOKEN_LENGTH: usize = 4;
+pub const SHARED_SECRET_LENGTH: usize = 16;
+
+pub type Encryptor = cfb8::Encryptor<Aes128>;
+pub type Decryptor = cfb8::Decryptor<Aes128>;
+
+// keypair used to exchange the shared secret, generated once per server start
+pub struct ServerKey {
+    private_key: RsaPrivateKey,
+    public_key_der: Vec<u8>
+}
+
+impl ServerKey {
+    pub fn generate() -> Result<Self> {
+        let private_key = RsaPrivateKey::new(&mut rand::thread_rng(), SERVER_KEY_BITS)?;
+        let public_key_der = private_key.to_public_key().to_public_key_der()
+            .map_err(|e| ProtocolError::Rsa(rsa::pkcs8::Error::PublicKey(e).into()))?
+            .into_vec();
+        Ok(ServerKey { private_key, public_key_der })
+    }
+
+    // the public key in the ASN.1 DER form sent in Encryption Request
+    pub fn public_key_der(&self) -> &[u8] {
+        &self.public_key_der
+    }
+
+    pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>> {
+        Ok(self.private_key.decrypt(Pkcs1v15Encrypt, data)?)
+    }
+
+    pub fn decrypt_shared_secret(&self, data: &[u8]) -> Result<[u8; SHARED_SECRET_LENGTH]> {
+        let shared_secret = self.decrypt(data)?;
+        shared_secret.as_slice().try_into()
+            .map_err(|_| ProtocolError::InvalidSharedSecret(shared_secret.len()))
+    }
+}
+
+pub fn generate_verify_token() -> [u8; VERIFY_TOKEN_LENGTH] {
+    let mut verify_token = [0; VERIFY_TOKEN_LENGTH];
+    rand::thread_rng().fill_bytes(&mut verify_token);
+    verify_token
+}
+
+// the shared secret is used both as the key and the initial vector
+pub fn ciphers(shared_secret: &[u8; SHARED_SECRET_LENGTH]) -> (Encryptor, Decryptor) {
+    (
+        Encryptor::new(shared_secret.into(), shared_secret.into()),
+        Decryptor::new(shared_secret.into(), shared_secret.into())
+    )
+}
+
+// cfb8 works on single byte blocks, so any slice can be processed in place
+pub fn encrypt(encryptor: &mut Encryptor, data: &mut [u8]) {
+    let (blocks, _) = InOutBuf::from(data).into_chunks();
+    encryptor.encrypt_blocks_inout_mut(blocks);
+}
+
+pub fn decrypt(decryptor: &mut Decryptor, data: &mut [u8]) {
+    let (blocks, _) = InOutBuf::from(data).into_chunks();
+    decryptor.decrypt_blocks_inout_mut(blocks);
+}
+
+// sha1 of server id + shared secret + public key, printed as a signed big-endian number in hex
+pub fn server_hash(server_id: &str, shared_secret: &[u8], public_key_der: &[u8]) -> String {
+    let mut digest: [u8; 20] = Sha1::new()
+        .chain_update(server_id.as_bytes())
+        .chain_update(shared_secret)
+        .chain_update(public_key_der)
+        .finalize()
+        .into();
+    let negative = digest[0] & 0x80 != 0;
+    if negative {
+        // two's complement
+        let mut carry = true;
+        for byte in digest.iter_mut().rev() {
+            *byte = !*byte;
+            if carry {
+                (*byte, carry) = byte.overflowing_add(1);
+            }
+        }
+    }
+    let hex: String = digest.iter().map(|byte| format!("{byte:02x}")).collect();
+    let hex = hex.trim_start_matches('0');
+    if negative { format!("-{hex}") } else { hex.to_string() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn server_hash_matches_vanilla() {
+        assert_eq!(server_hash("Notch", &[], &[]), "4ed1f46bbe04bc756bcb17c0c7ce3e4632f06a48");
+        assert_eq!(server_hash("jeb_", &[], &[]), "-7c9d5b0044c130109a5d7b5fb5c317c02b4e28c1");
+        assert_eq!(server_hash("simon", &[], &[]), "88e16a1019277b15d58faf0541e11910eb756f6");
+    }
+
+    #[test]
+    fn decrypts_shared_secret() {
+        let key = ServerKey::generate().unwrap();
+        let shared_secret = [7; SHARED_SECRET_LENGTH];
+        let encrypted = key.private_key.to_public_key()
+            .encrypt(&mut rand::thread_rng(), Pkcs1v15Encrypt, &shared_secret)
+            .unwrap();
+        assert_eq!(key.decrypt_shared_secret(&encrypted).unwrap(), shared_secret);
+        let encrypted = key.private_key.to_public_key()
+            .encrypt(&mut rand::thread_rng(), Pkcs1v15Encrypt, &[7; 8])
+            .unwrap();
+        assert!(matches!(key.decrypt_shared_secret(&encrypted), Err(ProtocolError::InvalidSharedSecret(8))));
+    }
+
+    #[test]
+    fn stream_cipher_round_trip() {
+        let (mut encryptor, _) = ciphers(&[1; SHARED_SECRET_LENGTH]);
+        let (_, mut decryptor) = ciphers(&[1; SHARED_SECRET_LENGTH]);
+        let mut data = b"hello world".to_vec();
+        // split calls have to continue the same stream
+        encrypt(&mut encryptor, &mut data[..4]);
+        encrypt(&mut encryptor, &mut data[4..]);
+        assert_ne!(data, b"hello world");
+        decrypt(&mut decryptor, &mut data[..7]);
+        decrypt(&mut decryptor, &mut data[7..]);
+        assert_eq!(data, b"hello world");
+    }
+}
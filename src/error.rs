@@ -0,0 +1,140 @@
+use std::fmt::{Display, Formatter};
+use std::io::ErrorKind;
+use std::string::FromUtf8Error;
+
+use crate::state::{ConnectionState, StateError};
+use crate::version::ProtocolVersion;
+
+#[derive(Debug)]
+pub enum ProtocolError {
+    VarIntTooLong,
+    VarLongTooLong,
+    UnexpectedEof,
+    InvalidUtf8(FromUtf8Error),
+    StringTooLong { length: usize, max: usize },
+    InvalidBoolean(u8),
+    ArrayTooLong { length: usize, max: usize },
+    InvalidIdentifier(String),
+    UnknownVariant { name: &'static str, id: u32 },
+    UnknownPacket { state: ConnectionState, id: u32 },
+    UnsupportedVersion(u32),
+    UnsupportedPacket { packet: &'static str, version: ProtocolVersion },
+    FrameTooLarge(usize),
+    InvalidDataLength { length: usize, threshold: usize },
+    Nbt(fastnbt::error::Error),
+    InvalidNbt(String),
+    InvalidText(String),
+    InvalidStatus(String),
+    InvalidFavicon(String),
+    InvalidLegacyPing(String),
+    InvalidForwarding(String),
+    UnknownPluginMessage(u32),
+    InvalidProxyHeader(String),
+    UnfinishedConfiguration(usize),
+    InvalidDatapack(String),
+    Rsa(rsa::Error),
+    InvalidSharedSecret(usize),
+    VerifyTokenMismatch,
+    AuthenticationFailed { username: String },
+    SessionService(reqwest::Error),
+    Disconnected(String),
+    State(StateError),
+    ConnectionClosed,
+    Io(std::io::Error)
+}
+
+pub type Result<T> = std::result::Result<T, ProtocolError>;
+
+impl Display for ProtocolError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProtocolError::VarIntTooLong => write!(f, "var int is more then 5 bytes long"),
+            ProtocolError::VarLongTooLong => write!(f, "var long is more then 10 bytes long"),
+            ProtocolError::UnexpectedEof => write!(f, "unexpected end of packet"),
+            ProtocolError::InvalidUtf8(e) => write!(f, "invalid utf-8 string: {e}"),
+            ProtocolError::StringTooLong { length, max } => write!(f, "string of length {length} is longer than {max}"),
+            ProtocolError::InvalidBoolean(value) => write!(f, "invalid boolean {value}"),
+            ProtocolError::ArrayTooLong { length, max } => write!(f, "array of length {length} is longer than {max}"),
+            ProtocolError::InvalidIdentifier(identifier) => write!(f, "invalid identifier {identifier}"),
+            ProtocolError::UnknownVariant { name, id } => write!(f, "unknown {name} variant {id}"),
+            ProtocolError::UnknownPacket { state, id } => write!(f, "unknown packet {id:#04x} in {state} state"),
+            ProtocolError::UnsupportedVersion(protocol) => write!(f, "unsupported protocol version {protocol}"),
+            ProtocolError::UnsupportedPacket { packet, version } => write!(f, "{packet} doesn't exist in {version}"),
+            ProtocolError::FrameTooLarge(length) => write!(f, "frame of {length} bytes is too large"),
+            ProtocolError::InvalidDataLength { length, threshold } => write!(f, "invalid data length {length} for compression threshold {threshold}"),
+            ProtocolError::Nbt(e) => write!(f, "invalid nbt: {e}"),
+            ProtocolError::InvalidNbt(reason) => write!(f, "invalid nbt: {reason}"),
+            ProtocolError::InvalidText(reason) => write!(f, "invalid text component: {reason}"),
+            ProtocolError::InvalidStatus(reason) => write!(f, "invalid status response: {reason}"),
+            ProtocolError::InvalidFavicon(reason) => write!(f, "invalid favicon: {reason}"),
+            ProtocolError::InvalidLegacyPing(reason) => write!(f, "invalid legacy ping: {reason}"),
+            ProtocolError::InvalidForwarding(reason) => write!(f, "invalid forwarded player data: {reason}"),
+            ProtocolError::UnknownPluginMessage(id) => write!(f, "login plugin response to unknown message {id}"),
+            ProtocolError::InvalidProxyHeader(reason) => write!(f, "invalid proxy protocol header: {reason}"),
+            ProtocolError::UnfinishedConfiguration(tasks) => write!(f, "finished configuration with {tasks} tasks still running"),
+            ProtocolError::InvalidDatapack(reason) => write!(f, "invalid datapack: {reason}"),
+            ProtocolError::Rsa(e) => write!(f, "rsa error: {e}"),
+            ProtocolError::InvalidSharedSecret(length) => write!(f, "shared secret of {length} bytes, expected 16"),
+            ProtocolError::VerifyTokenMismatch => write!(f, "verify token doesn't match"),
+            ProtocolError::AuthenticationFailed { username } => write!(f, "failed to verify username {username}"),
+            ProtocolError::SessionService(e) => write!(f, "session service request failed: {e}"),
+            ProtocolError::Disconnected(reason) => write!(f, "disconnected the client: {reason}"),
+            ProtocolError::State(e) => write!(f, "{e}"),
+            ProtocolError::ConnectionClosed => write!(f, "connection closed"),
+            ProtocolError::Io(e) => write!(f, "{e}")
+        }
+    }
+}
+
+impl std::error::Error for ProtocolError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ProtocolError::InvalidUtf8(e) => Some(e),
+            ProtocolError::Nbt(e) => Some(e),
+            ProtocolError::Rsa(e) => Some(e),
+            ProtocolError::SessionService(e) => Some(e),
+            ProtocolError::State(e) => Some(e),
+            ProtocolError::Io(e) => Some(e),
+            _ => None
+        }
+    }
+}
+
+impl From<std::io::Error> for ProtocolError {
+    fn from(error: std::io::Error) -> Self {
+        match error.kind() {
+            ErrorKind::UnexpectedEof => ProtocolError::UnexpectedEof,
+            _ => ProtocolError::Io(error)
+        }
+    }
+}
+
+impl From<FromUtf8Error> for ProtocolError {
+    fn from(error: FromUtf8Error) -> Self {
+        ProtocolError::InvalidUtf8(error)
+    }
+}
+
+impl From<fastnbt::error::Error> for ProtocolError {
+    fn from(error: fastnbt::error::Error) -> Self {
+        ProtocolError::Nbt(error)
+    }
+}
+
+impl From<rsa::Error> for ProtocolError {
+    fn from(error: rsa::Error) -> Self {
+        ProtocolError::Rsa(error)
+    }
+}
+
+impl From<reqwest::Error> for ProtocolError {
+    fn from(error: reqwest::Error) -> Self {
+        ProtocolError::SessionService(error)
+    }
+}
+
+impl From<StateError> for ProtocolError {
+    fn from(error: StateError) -> Self {
+        ProtocolError::State(error)
+    }
+}
@@ -0,0 +1,135 @@
+use std::net::IpAddr;
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use uuid::Uuid;
+
+use crate::codec::{ByteBuffer, MinecraftReadTypes};
+use crate::error::{ProtocolError, Result};
+use crate::packets::login::Property;
+
+pub const VELOCITY_CHANNEL: &str = "velocity:player_info";
+// the only forwarding version we ask for, newer ones add the player's chat signing key
+pub const VELOCITY_FORWARDING_VERSION: u8 = 1;
+const VELOCITY_SIGNATURE_LENGTH: usize = 32;
+
+// what a proxy in front of the server tells us about the player it connects for
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForwardedPlayer {
+    pub address: IpAddr,
+    pub uuid: Uuid,
+    // BungeeCord doesn't forward the name, the one from Login Start is used then
+    pub name: Option<String>,
+    pub properties: Vec<Property>
+}
+
+// BungeeCord appends `\0client ip\0uuid without dashes\0properties json` to the handshake's server address,
+// the properties are missing when the proxy runs in offline mode
+pub fn parse_bungeecord(server_address: &str) -> Result<(String, ForwardedPlayer)> {
+    let mut parts = server_address.split('\0');
+    let host = parts.next().unwrap_or_default().to_string();
+    let (Some(address), Some(uuid)) = (parts.next(), parts.next()) else {
+        return Err(ProtocolError::InvalidForwarding("server address doesn't contain forwarded data".to_string()));
+    };
+    let address = address.parse()
+        .map_err(|_| ProtocolError::InvalidForwarding(format!("invalid address {address}")))?;
+    let uuid = Uuid::try_parse(uuid)
+        .map_err(|_| ProtocolError::InvalidForwarding(format!("invalid uuid {uuid}")))?;
+    let properties = match parts.next() {
+        Some(properties) => serde_json::from_str(properties)
+            .map_err(|e| ProtocolError::InvalidForwarding(format!("invalid properties: {e}")))?,
+        None => vec![]
+    };
+    Ok((host, ForwardedPlayer { address, uuid, name: None, properties }))
+}
+
+// data of the Login Plugin Request on VELOCITY_CHANNEL
+pub fn velocity_request_data() -> Vec<u8> {
+    vec![VELOCITY_FORWARDING_VERSION]
+}
+
+// Velocity answers with an HMAC-SHA256 signature made with the shared forwarding secret,
+// followed by the version, client address, uuid, name and properties
+pub fn parse_velocity(data: &[u8], secret: &[u8]) -> Result<ForwardedPlayer> {
+    if data.len() < VELOCITY_SIGNATURE_LENGTH {
+        return Err(ProtocolError::InvalidForwarding("missing signature".to_string()));
+    }
+    let (signature, payload) = data.split_at(VELOCITY_SIGNATURE_LENGTH);
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("hmac takes keys of any length");
+    mac.update(payload);
+    mac.verify_slice(signature)
+        .map_err(|_| ProtocolError::InvalidForwarding("signature doesn't match the forwarding secret".to_string()))?;
+
+    let mut buffer = ByteBuffer::from_bytes(payload);
+    let version = buffer.read_var_int()?;
+    if version != VELOCITY_FORWARDING_VERSION as u32 {
+        return Err(ProtocolError::InvalidForwarding(format!("unsupported forwarding version {version}")));
+    }
+    let address = buffer.read_var_string()?;
+    let address = address.parse()
+        .map_err(|_| ProtocolError::InvalidForwarding(format!("invalid address {address}")))?;
+    let uuid = buffer.read_uuid()?;
+    let name = buffer.read_var_string()?;
+    let properties = buffer.read_length_prefixed()?;
+    Ok(ForwardedPlayer { address, uuid, name: Some(name), properties })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::MinecraftWriteTypes;
+
+    fn velocity_response(player: &ForwardedPlayer, secret: &[u8]) -> Vec<u8> {
+        let mut payload = ByteBuffer::new();
+        payload.write_var_int(VELOCITY_FORWARDING_VERSION as u32);
+        payload.write_var_string(&player.address.to_string());
+        payload.write_uuid(&player.uuid);
+        payload.write_var_string(player.name.as_deref().unwrap());
+        payload.write_length_prefixed(&player.properties).unwrap();
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret).unwrap();
+        mac.update(payload.as_bytes());
+        [mac.finalize().into_bytes().as_slice(), payload.as_bytes()].concat()
+    }
+
+    #[test]
+    fn bungeecord_server_address() {
+        let (host, player) = parse_bungeecord(
+            "play.example.com\u{0}203.0.113.7\u{0}069a79f444e94726a5befca90e38aaf5\u{0}[{\"name\":\"textures\",\"value\":\"e30=\",\"signature\":\"c2ln\"}]"
+        ).unwrap();
+        assert_eq!(host, "play.example.com");
+        assert_eq!(player, ForwardedPlayer {
+            address: "203.0.113.7".parse().unwrap(),
+            uuid: Uuid::from_u128(0x069a79f444e94726a5befca90e38aaf5),
+            name: None,
+            properties: vec![Property { name: "textures".to_string(), value: "e30=".to_string(), signature: Some("c2ln".to_string()) }]
+        });
+
+        let (_, offline) = parse_bungeecord("localhost\u{0}::1\u{0}00000000000000000000000000000001").unwrap();
+        assert_eq!(offline.address, "::1".parse::<IpAddr>().unwrap());
+        assert!(offline.properties.is_empty());
+
+        assert!(parse_bungeecord("localhost").is_err());
+        assert!(parse_bungeecord("localhost\u{0}not an ip\u{0}00000000000000000000000000000001").is_err());
+        assert!(parse_bungeecord("localhost\u{0}127.0.0.1\u{0}nope").is_err());
+    }
+
+    #[test]
+    fn velocity_player_info() {
+        let player = ForwardedPlayer {
+            address: "198.51.100.2".parse().unwrap(),
+            uuid: Uuid::from_u128(7),
+            name: Some("Notch".to_string()),
+            properties: vec![Property { name: "textures".to_string(), value: "e30=".to_string(), signature: None }]
+        };
+        let response = velocity_response(&player, b"secret");
+        assert_eq!(parse_velocity(&response, b"secret").unwrap(), player);
+        assert_eq!(
+            parse_velocity(&response, b"other secret").unwrap_err().to_string(),
+            "invalid forwarded player data: signature doesn't match the forwarding secret"
+        );
+        let mut tampered = response.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(parse_velocity(&tampered, b"secret").is_err());
+        assert!(parse_velocity(&response[..20], b"secret").is_err());
+    }
+}
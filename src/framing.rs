@@ -0,0 +1,296 @@
+use std::io::{Read, Write};
+use bytebuffer::ByteBuffer;
+use bytes::{Buf, BufMut, BytesMut};
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use tokio::io::AsyncWriteExt;
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::codec::MinecraftReadTypes;
+use crate::encryption::{self, Decryptor, Encryptor, SHARED_SECRET_LENGTH};
+use crate::error::{ProtocolError, Result};
+
+// the length prefix of a frame is at most a 3 byte var int
+pub const MAX_FRAME_LENGTH: usize = 2097151;
+// same limit the vanilla server puts on the uncompressed size of a packet
+pub const MAX_DATA_LENGTH: usize = 8388608;
+const MAX_LENGTH_BYTES: usize = 3;
+
+// splits the byte stream into length-prefixed frames, each yielded as packet id + packet data.
+// once a compression threshold is set every frame starts with the uncompressed data length,
+// 0 meaning the rest of the frame isn't compressed. With encryption enabled the whole stream,
+// length prefixes included, goes through AES/CFB8
+#[derive(Clone, Default)]
+pub struct MinecraftFrameCodec {
+    compression_threshold: Option<usize>,
+    encryptor: Option<Encryptor>,
+    decryptor: Option<Decryptor>,
+    // bytes at the start of the read buffer that are already decrypted
+    decrypted: usize
+}
+
+impl MinecraftFrameCodec {
+    pub fn new() -> Self {
+        MinecraftFrameCodec::default()
+    }
+
+    // bytes still in the read buffer are treated as encrypted
+    pub fn enable_encryption(&mut self, shared_secret: &[u8; SHARED_SECRET_LENGTH]) {
+        let (encryptor, decryptor) = encryption::ciphers(shared_secret);
+        self.encryptor = Some(encryptor);
+        self.decryptor = Some(decryptor);
+        self.decrypted = 0;
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        self.encryptor.is_some()
+    }
+
+    pub fn compression_threshold(&self) -> Option<usize> {
+        self.compression_threshold
+    }
+
+    pub fn set_compression_threshold(&mut self, threshold: Option<usize>) {
+        self.compression_threshold = threshold;
+    }
+}
+
+fn put_var_int(dst: &mut BytesMut, value: u32) {
+    let mut remaining = value;
+    loop {
+        if remaining & !0x7F == 0 {
+            dst.put_u8(remaining as u8);
+            break;
+        }
+        dst.put_u8((remaining & 0x7F | 0x80) as u8);
+        remaining >>= 7;
+    }
+}
+
+fn var_int_length(value: u32) -> usize {
+    let mut length = 1;
+    let mut remaining = value >> 7;
+    while remaining != 0 {
+        length += 1;
+        remaining >>= 7;
+    }
+    length
+}
+
+fn decompress(mut frame: ByteBuffer, threshold: usize) -> Result<ByteBuffer> {
+    let data_length = frame.read_var_int()? as usize;
+    let compressed = &frame.as_bytes()[frame.get_rpos()..];
+    if data_length == 0 {
+        return Ok(ByteBuffer::from_bytes(compressed));
+    }
+    if data_length < threshold || data_length > MAX_DATA_LENGTH {
+        return Err(ProtocolError::InvalidDataLength { length: data_length, threshold });
+    }
+    let mut data = Vec::with_capacity(data_length);
+    ZlibDecoder::new(compressed).take(data_length as u64 + 1).read_to_end(&mut data)?;
+    if data.len() != data_length {
+        return Err(ProtocolError::InvalidDataLength { length: data.len(), threshold });
+    }
+    Ok(ByteBuffer::from_vec(data))
+}
+
+// returns the frame length and the size of its prefix, or None if the prefix isn't complete yet
+fn peek_frame_length(src: &[u8]) -> Result<Option<(usize, usize)>> {
+    let mut length: usize = 0;
+    for (index, byte) in src.iter().take(MAX_LENGTH_BYTES).enumerate() {
+        length |= ((byte & 0x7F) as usize) << (7 * index);
+        if byte & 0x80 == 0 {
+            return Ok(Some((length, index + 1)));
+        }
+    }
+    if src.len() >= MAX_LENGTH_BYTES {
+        return Err(ProtocolError::VarIntTooLong);
+    }
+    Ok(None)
+}
+
+impl Decoder for MinecraftFrameCodec {
+    type Item = ByteBuffer;
+    type Error = ProtocolError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<ByteBuffer>> {
+        if let Some(decryptor) = &mut self.decryptor {
+            encryption::decrypt(decryptor, &mut src[self.decrypted..]);
+            self.decrypted = src.len();
+        }
+        let Some((length, prefix_length)) = peek_frame_length(src)? else {
+            return Ok(None);
+        };
+        if length > MAX_FRAME_LENGTH {
+            return Err(ProtocolError::FrameTooLarge(length));
+        }
+        if src.len() < prefix_length + length {
+            src.reserve(prefix_length + length - src.len());
+            return Ok(None);
+        }
+        src.advance(prefix_length);
+        let frame = ByteBuffer::from_bytes(&src.split_to(length));
+        self.decrypted = self.decrypted.saturating_sub(prefix_length + length);
+        match self.compression_threshold {
+            Some(threshold) => decompress(frame, threshold).map(Some),
+            None => Ok(Some(frame))
+        }
+    }
+}
+
+impl Encoder<ByteBuffer> for MinecraftFrameCodec {
+    type Error = ProtocolError;
+
+    fn encode(&mut self, item: ByteBuffer, dst: &mut BytesMut) -> Result<()> {
+        let start = dst.len();
+        let data = &item.as_bytes()[item.get_rpos()..item.get_wpos()];
+        match self.compression_threshold {
+            None => put_frame(dst, &[], data)?,
+            Some(threshold) if data.len() < threshold => put_frame(dst, &[0], data)?,
+            Some(_) => {
+                let mut data_length = BytesMut::new();
+                put_var_int(&mut data_length, data.len() as u32);
+                let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(data)?;
+                put_frame(dst, &data_length, &encoder.finish()?)?
+            }
+        }
+        if let Some(encryptor) = &mut self.encryptor {
+            encryption::encrypt(encryptor, &mut dst[start..]);
+        }
+        Ok(())
+    }
+}
+
+fn put_frame(dst: &mut BytesMut, header: &[u8], data: &[u8]) -> Result<()> {
+    let length = header.len() + data.len();
+    if length > MAX_FRAME_LENGTH {
+        return Err(ProtocolError::FrameTooLarge(length));
+    }
+    dst.reserve(var_int_length(length as u32) + length);
+    put_var_int(dst, length as u32);
+    dst.put_slice(header);
+    dst.put_slice(data);
+    Ok(())
+}
+
+// frames a single packet with MinecraftFrameCodec and writes it straight to the socket
+pub async fn write_packet<T>(socket: &mut T, buffer: &mut ByteBuffer) -> Result<()>
+where T: AsyncWriteExt + Unpin {
+    let mut framed_write_buffer = BytesMut::new();
+    MinecraftFrameCodec::new().encode(std::mem::take(buffer), &mut framed_write_buffer)?;
+    socket.write_all(&framed_write_buffer).await?;
+    socket.flush().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(bytes: &[u8]) -> BytesMut {
+        let mut dst = BytesMut::new();
+        MinecraftFrameCodec::new().encode(ByteBuffer::from_bytes(bytes), &mut dst).unwrap();
+        dst
+    }
+
+    #[test]
+    fn decodes_frames_split_across_reads() {
+        let mut stream = frame(&[0x00, 1, 2, 3]);
+        stream.extend_from_slice(&frame(&[0x01; 300]));
+        let mut codec = MinecraftFrameCodec::new();
+        let mut src = BytesMut::new();
+        let mut frames = Vec::new();
+        for byte in stream {
+            src.put_u8(byte);
+            while let Some(frame) = codec.decode(&mut src).unwrap() {
+                frames.push(frame.into_vec());
+            }
+        }
+        assert_eq!(frames, vec![vec![0x00, 1, 2, 3], vec![0x01; 300]]);
+        assert!(src.is_empty());
+    }
+
+    #[test]
+    fn encodes_length_prefix() {
+        assert_eq!(&frame(&[0x00])[..], &[1, 0x00]);
+        assert_eq!(&frame(&[0x07; 128])[..2], &[0x80, 0x01]);
+    }
+
+    #[test]
+    fn rejects_oversized_frames() {
+        let mut codec = MinecraftFrameCodec::new();
+        // 2097152 encoded as a var int
+        let mut src = BytesMut::from(&[0x80, 0x80, 0x80, 0x01][..]);
+        assert!(matches!(codec.decode(&mut src), Err(ProtocolError::VarIntTooLong)));
+        let mut src = BytesMut::from(&[0xff, 0xff, 0x7f][..]);
+        assert!(codec.decode(&mut src).unwrap().is_none());
+        let too_large = ByteBuffer::from_vec(vec![0; MAX_FRAME_LENGTH + 1]);
+        assert!(matches!(codec.encode(too_large, &mut BytesMut::new()), Err(ProtocolError::FrameTooLarge(_))));
+    }
+
+    fn compressed_round_trip(data: &[u8]) -> BytesMut {
+        let mut codec = MinecraftFrameCodec::new();
+        codec.set_compression_threshold(Some(256));
+        let mut dst = BytesMut::new();
+        codec.encode(ByteBuffer::from_bytes(data), &mut dst).unwrap();
+        let framed = dst.clone();
+        assert_eq!(codec.decode(&mut dst).unwrap().unwrap().into_vec(), data);
+        assert!(dst.is_empty());
+        framed
+    }
+
+    #[test]
+    fn leaves_packets_below_threshold_uncompressed() {
+        let framed = compressed_round_trip(&[0x01; 255]);
+        // frame length 256, data length 0, then the raw packet
+        assert_eq!(&framed[..3], &[0x80, 0x02, 0x00]);
+        assert_eq!(&framed[3..], &[0x01; 255]);
+    }
+
+    #[test]
+    fn compresses_packets_above_threshold() {
+        let framed = compressed_round_trip(&[0x01; 4096]);
+        let mut frame = ByteBuffer::from_bytes(&framed);
+        let length = frame.read_var_int().unwrap() as usize;
+        assert_eq!(length, frame.readabe_bytes());
+        assert_eq!(frame.read_var_int().unwrap(), 4096);
+        assert!(length < 4096);
+    }
+
+    #[test]
+    fn rejects_compressed_frames_below_threshold() {
+        let mut codec = MinecraftFrameCodec::new();
+        let mut dst = BytesMut::new();
+        codec.set_compression_threshold(Some(0));
+        codec.encode(ByteBuffer::from_bytes(&[0x01; 16]), &mut dst).unwrap();
+        codec.set_compression_threshold(Some(256));
+        assert!(matches!(codec.decode(&mut dst), Err(ProtocolError::InvalidDataLength { length: 16, threshold: 256 })));
+    }
+
+    #[test]
+    fn encrypted_frames_split_across_reads() {
+        let mut writer = MinecraftFrameCodec::new();
+        writer.enable_encryption(&[3; SHARED_SECRET_LENGTH]);
+        writer.set_compression_threshold(Some(64));
+        let mut stream = BytesMut::new();
+        writer.encode(ByteBuffer::from_bytes(&[0x00, 1, 2, 3]), &mut stream).unwrap();
+        writer.encode(ByteBuffer::from_bytes(&[0x01; 300]), &mut stream).unwrap();
+        assert_ne!(&stream[..3], &[5, 0, 0x00]);
+
+        let mut reader = MinecraftFrameCodec::new();
+        reader.enable_encryption(&[3; SHARED_SECRET_LENGTH]);
+        reader.set_compression_threshold(Some(64));
+        let mut src = BytesMut::new();
+        let mut frames = Vec::new();
+        for chunk in stream.chunks(7) {
+            src.extend_from_slice(chunk);
+            while let Some(frame) = reader.decode(&mut src).unwrap() {
+                frames.push(frame.into_vec());
+            }
+        }
+        assert_eq!(frames, vec![vec![0x00, 1, 2, 3], vec![0x01; 300]]);
+        assert!(src.is_empty());
+    }
+}
@@ -0,0 +1,223 @@
+use std::time::Duration;
+
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::net::TcpStream;
+use tokio::time::{sleep, timeout, Instant};
+
+use crate::error::{ProtocolError, Result};
+use crate::status::MinecraftStatus;
+
+// first byte of every pre 1.7 server list ping. Modern frames of 254, 382, ... bytes start with it too,
+// as the first byte of their VarInt length, so the bytes after it decide
+pub const LEGACY_PING_ID: u8 = 0xfe;
+const KICK_ID: u8 = 0xff;
+const PLUGIN_MESSAGE_ID: u8 = 0xfa;
+const PING_HOST_CHANNEL: &str = "MC|PingHost";
+// clients before 1.6 send nothing after the first bytes, so a short pause ends their ping
+pub const LEGACY_PING_TIMEOUT: Duration = Duration::from_millis(100);
+const MAX_LEGACY_PING_LENGTH: usize = 512;
+// how often the socket is peeked again while a ping may still be incomplete
+const PEEK_INTERVAL: Duration = Duration::from_millis(10);
+// sent instead of our real protocol like vanilla does, old clients then show the version name as incompatible
+pub const LEGACY_PROTOCOL: i32 = 127;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LegacyPing {
+    // beta 1.8 to 1.3, only `0xfe`
+    Beta,
+    // 1.4 and 1.5, `0xfe 0x01`
+    V1_4,
+    // 1.6 follows up with an MC|PingHost plugin message
+    V1_6 { protocol: u8, host: String, port: i32 }
+}
+
+impl LegacyPing {
+    // Ok(None) while the ping is incomplete. Beta and V1_4 are prefixes of the 1.6 ping,
+    // so they're only final once the client stops sending
+    pub fn parse(bytes: &[u8]) -> Result<Option<LegacyPing>> {
+        match bytes {
+            [] => Ok(None),
+            [LEGACY_PING_ID] => Ok(Some(LegacyPing::Beta)),
+            [LEGACY_PING_ID, 0x01] => Ok(Some(LegacyPing::V1_4)),
+            [LEGACY_PING_ID, 0x01, PLUGIN_MESSAGE_ID, ping_host @ ..] => parse_ping_host(ping_host),
+            _ => Err(ProtocolError::InvalidLegacyPing("unknown ping format".to_string()))
+        }
+    }
+
+    // the kick packet older clients read the server list entry from
+    pub fn response(&self, status: &MinecraftStatus) -> Vec<u8> {
+        let (online, max) = status.players.as_ref().map_or((0, 0), |players| (players.online, players.max));
+        let text = match self {
+            // fields are separated by section signs, so the motd can't have any formatting
+            LegacyPing::Beta => format!("{}§{online}§{max}", status.description.to_plain().replace('§', "")),
+            LegacyPing::V1_4 | LegacyPing::V1_6 { .. } => format!(
+                "§1\0{LEGACY_PROTOCOL}\0{}\0{}\0{online}\0{max}",
+                status.version.name.replace('\0', ""),
+                status.description.to_legacy().replace('\0', "")
+            )
+        };
+        let mut response = vec![KICK_ID];
+        put_string(&mut response, &text);
+        response
+    }
+}
+
+// looks at the start of the connection without consuming it, like vanilla's LegacyQueryHandler.
+// Anything that isn't a legacy ping is left for the modern handshake
+pub async fn is_legacy_ping(socket: &TcpStream) -> bool {
+    let deadline = Instant::now() + LEGACY_PING_TIMEOUT;
+    let mut bytes = [0; MAX_LEGACY_PING_LENGTH];
+    loop {
+        let Ok(read) = socket.peek(&mut bytes).await else {
+            return false;
+        };
+        match LegacyPing::parse(&bytes[..read]) {
+            Err(_) => return false,
+            Ok(Some(LegacyPing::V1_6 { .. })) => return true,
+            // Beta and V1_4 are only final once the client stops sending
+            Ok(ping) if read == bytes.len() || Instant::now() >= deadline => return ping.is_some(),
+            Ok(_) => sleep(PEEK_INTERVAL).await
+        }
+    }
+}
+
+// reads the rest of a connection that is_legacy_ping accepted
+pub async fn read_legacy_ping<R: AsyncRead + Unpin>(reader: &mut R) -> Result<LegacyPing> {
+    let mut bytes = Vec::new();
+    let mut chunk = [0; MAX_LEGACY_PING_LENGTH];
+    loop {
+        let ping = LegacyPing::parse(&bytes)?;
+        if let Some(ping @ LegacyPing::V1_6 { .. }) = ping {
+            return Ok(ping);
+        }
+        let read = match timeout(LEGACY_PING_TIMEOUT, reader.read(&mut chunk)).await {
+            Ok(read) => read?,
+            Err(_) => 0
+        };
+        if read == 0 {
+            return ping.ok_or(ProtocolError::UnexpectedEof);
+        }
+        bytes.extend_from_slice(&chunk[..read]);
+        if bytes.len() > MAX_LEGACY_PING_LENGTH {
+            return Err(ProtocolError::InvalidLegacyPing(format!("ping is longer than {MAX_LEGACY_PING_LENGTH} bytes")));
+        }
+    }
+}
+
+// strings are prefixed with their length in UTF-16 code units and sent as UTF-16BE
+fn put_string(buffer: &mut Vec<u8>, text: &str) {
+    let units: Vec<u16> = text.encode_utf16().collect();
+    buffer.extend_from_slice(&(units.len() as u16).to_be_bytes());
+    for unit in units {
+        buffer.extend_from_slice(&unit.to_be_bytes());
+    }
+}
+
+// reads a string at the start of `bytes`, None if it's incomplete
+fn take_string(bytes: &[u8]) -> Result<Option<(String, &[u8])>> {
+    let Some((length, rest)) = take_short(bytes) else {
+        return Ok(None);
+    };
+    let length = length as usize * 2;
+    if rest.len() < length {
+        return Ok(None);
+    }
+    let units: Vec<u16> = rest[..length].chunks(2).map(|unit| u16::from_be_bytes([unit[0], unit[1]])).collect();
+    let text = String::from_utf16(&units).map_err(|e| ProtocolError::InvalidLegacyPing(e.to_string()))?;
+    Ok(Some((text, &rest[length..])))
+}
+
+fn take_short(bytes: &[u8]) -> Option<(u16, &[u8])> {
+    Some((u16::from_be_bytes(bytes.get(..2)?.try_into().unwrap()), &bytes[2..]))
+}
+
+fn parse_ping_host(bytes: &[u8]) -> Result<Option<LegacyPing>> {
+    let Some((channel, rest)) = take_string(bytes)? else {
+        return Ok(None);
+    };
+    if channel != PING_HOST_CHANNEL {
+        return Err(ProtocolError::InvalidLegacyPing(format!("unexpected channel {channel}")));
+    }
+    let Some((length, data)) = take_short(rest) else {
+        return Ok(None);
+    };
+    if data.len() < length as usize {
+        return Ok(None);
+    }
+    let invalid = || ProtocolError::InvalidLegacyPing("malformed MC|PingHost data".to_string());
+    let (&protocol, data) = data[..length as usize].split_first().ok_or_else(invalid)?;
+    let (host, data) = take_string(data)?.ok_or_else(invalid)?;
+    let port: [u8; 4] = data.try_into().map_err(|_| invalid())?;
+    Ok(Some(LegacyPing::V1_6 { protocol, host, port: i32::from_be_bytes(port) }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::status::{StatusPlayers, StatusVersion};
+    use crate::text::TextComponent;
+
+    fn ping_1_6(host: &str, port: i32) -> Vec<u8> {
+        let mut data = vec![78];
+        put_string(&mut data, host);
+        data.extend_from_slice(&port.to_be_bytes());
+        let mut ping = vec![LEGACY_PING_ID, 0x01, PLUGIN_MESSAGE_ID];
+        put_string(&mut ping, PING_HOST_CHANNEL);
+        ping.extend_from_slice(&(data.len() as u16).to_be_bytes());
+        ping.extend_from_slice(&data);
+        ping
+    }
+
+    #[test]
+    fn parses_every_variant() {
+        assert_eq!(LegacyPing::parse(&[0xfe]).unwrap(), Some(LegacyPing::Beta));
+        assert_eq!(LegacyPing::parse(&[0xfe, 0x01]).unwrap(), Some(LegacyPing::V1_4));
+        let ping = ping_1_6("localhost", 25565);
+        assert_eq!(
+            LegacyPing::parse(&ping).unwrap(),
+            Some(LegacyPing::V1_6 { protocol: 78, host: "localhost".to_string(), port: 25565 })
+        );
+        for length in 3..ping.len() {
+            assert_eq!(LegacyPing::parse(&ping[..length]).unwrap(), None);
+        }
+        assert!(LegacyPing::parse(&[0xfe, 0x02]).is_err());
+        let mut other_channel = ping.clone();
+        other_channel[6] = b'X';
+        assert!(LegacyPing::parse(&other_channel).is_err());
+    }
+
+    #[test]
+    fn responses() {
+        let status = MinecraftStatus {
+            version: StatusVersion { name: "1.20.4".to_string(), protocol: 765 },
+            players: Some(StatusPlayers { max: 100, online: 3, sample: vec![] }),
+            description: TextComponent::text("A §server").color("gold"),
+            favicon: None,
+            enforces_secure_chat: None,
+            previews_chat: None
+        };
+        let response = LegacyPing::Beta.response(&status);
+        assert_eq!(response[..3], [0xff, 0, 14]);
+        assert_eq!(take_string(&response[1..]).unwrap().unwrap().0, "A server§3§100");
+
+        let response = LegacyPing::V1_4.response(&status);
+        let (text, rest) = take_string(&response[1..]).unwrap().unwrap();
+        assert_eq!(text, "§1\u{0}127\u{0}1.20.4\u{0}§6A §server\u{0}3\u{0}100");
+        assert!(rest.is_empty());
+    }
+
+    #[tokio::test]
+    async fn waits_for_more_bytes_before_answering() {
+        let ping = ping_1_6("example.com", 25565);
+        let (mut client, mut server) = tokio::io::duplex(64);
+        let (first, second) = ping.split_at(5);
+        tokio::io::AsyncWriteExt::write_all(&mut client, first).await.unwrap();
+        let read = tokio::spawn(async move { read_legacy_ping(&mut server).await });
+        tokio::io::AsyncWriteExt::write_all(&mut client, second).await.unwrap();
+        assert!(matches!(read.await.unwrap().unwrap(), LegacyPing::V1_6 { port: 25565, .. }));
+
+        let (mut client, mut server) = tokio::io::duplex(64);
+        tokio::io::AsyncWriteExt::write_all(&mut client, &[0xfe, 0x01]).await.unwrap();
+        assert_eq!(read_legacy_ping(&mut server).await.unwrap(), LegacyPing::V1_4);
+    }
+}
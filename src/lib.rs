@@ -0,0 +1,25 @@
+// lets #[derive(McEncode, McDecode)] refer to `::minecraft_protocol` from inside this crate
+extern crate self as minecraft_protocol;
+
+pub mod codec;
+pub mod config;
+pub mod configuration;
+pub mod connection;
+pub mod encryption;
+pub mod error;
+pub mod forwarding;
+pub mod framing;
+pub mod legacy;
+pub mod login_plugin;
+pub mod nbt;
+pub mod packets;
+pub mod proxy_protocol;
+pub mod registry;
+pub mod server_state;
+pub mod session;
+pub mod state;
+pub mod status;
+pub mod text;
+pub mod types;
+pub mod version;
+pub mod world;
@@ -0,0 +1,60 @@
+use std::collections::HashMap;
+
+use crate::error::{ProtocolError, Result};
+use crate::packets::login::{LoginPluginRequest, LoginPluginResponse};
+use crate::types::Identifier;
+
+// Login Plugin Requests sent on one connection that the client hasn't answered yet,
+// so a response can be matched to the channel it answers
+#[derive(Debug, Default)]
+pub struct LoginPluginRequests {
+    next_message_id: u32,
+    pending: HashMap<u32, Identifier>
+}
+
+impl LoginPluginRequests {
+    pub fn new() -> Self {
+        LoginPluginRequests::default()
+    }
+
+    // picks the message id, the returned packet still has to be sent
+    pub fn request(&mut self, channel: Identifier, data: Vec<u8>) -> LoginPluginRequest {
+        let message_id = self.next_message_id;
+        self.next_message_id += 1;
+        self.pending.insert(message_id, channel.clone());
+        LoginPluginRequest { message_id, channel, data }
+    }
+
+    // the channel the response belongs to and its data, None when the client didn't understand the channel
+    pub fn response(&mut self, response: LoginPluginResponse) -> Result<(Identifier, Option<Vec<u8>>)> {
+        match self.pending.remove(&response.message_id) {
+            Some(channel) => Ok((channel, response.data)),
+            None => Err(ProtocolError::UnknownPluginMessage(response.message_id))
+        }
+    }
+
+    // Login Success has to wait until every request is answered
+    pub fn is_waiting(&self) -> bool {
+        !self.pending.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_responses_to_channels() {
+        let mut requests = LoginPluginRequests::new();
+        let first = requests.request(Identifier::parse("velocity:player_info").unwrap(), vec![1]);
+        let second = requests.request(Identifier::parse("example:hello").unwrap(), vec![]);
+        assert_ne!(first.message_id, second.message_id);
+
+        let (channel, data) = requests.response(LoginPluginResponse { message_id: second.message_id, data: None }).unwrap();
+        assert_eq!((channel.to_string(), data), ("example:hello".to_string(), None));
+        assert!(requests.is_waiting());
+        assert!(requests.response(LoginPluginResponse { message_id: second.message_id, data: None }).is_err());
+        requests.response(LoginPluginResponse { message_id: first.message_id, data: Some(vec![2]) }).unwrap();
+        assert!(!requests.is_waiting());
+    }
+}
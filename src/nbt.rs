@@ -0,0 +1,376 @@
+// network nbt: since 1.20.2 the root tag is sent as its type id and payload, without the name
+use std::collections::HashMap;
+
+use fastnbt::{ByteArray, IntArray, LongArray, Value};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::codec::{ByteBuffer, MinecraftReadTypes};
+use crate::error::{ProtocolError, Result};
+
+// same nesting limit as the vanilla NbtAccounter
+pub const MAX_DEPTH: usize = 512;
+// strings and names are prefixed with an unsigned short
+pub const MAX_STRING_LENGTH: usize = u16::MAX as usize;
+
+const TAG_END: u8 = 0;
+const TAG_BYTE: u8 = 1;
+const TAG_SHORT: u8 = 2;
+const TAG_INT: u8 = 3;
+const TAG_LONG: u8 = 4;
+const TAG_FLOAT: u8 = 5;
+const TAG_DOUBLE: u8 = 6;
+const TAG_BYTE_ARRAY: u8 = 7;
+const TAG_STRING: u8 = 8;
+const TAG_LIST: u8 = 9;
+const TAG_COMPOUND: u8 = 10;
+const TAG_INT_ARRAY: u8 = 11;
+const TAG_LONG_ARRAY: u8 = 12;
+
+fn tag_id(value: &Value) -> u8 {
+    match value {
+        Value::Byte(_) => TAG_BYTE,
+        Value::Short(_) => TAG_SHORT,
+        Value::Int(_) => TAG_INT,
+        Value::Long(_) => TAG_LONG,
+        Value::Float(_) => TAG_FLOAT,
+        Value::Double(_) => TAG_DOUBLE,
+        Value::ByteArray(_) => TAG_BYTE_ARRAY,
+        Value::String(_) => TAG_STRING,
+        Value::List(_) => TAG_LIST,
+        Value::Compound(_) => TAG_COMPOUND,
+        Value::IntArray(_) => TAG_INT_ARRAY,
+        Value::LongArray(_) => TAG_LONG_ARRAY
+    }
+}
+
+// writes any tag as a nameless root. Lists take the type of their first element, nbt lists can't mix types
+pub fn write_tag(buffer: &mut ByteBuffer, value: &Value) -> Result<()> {
+    buffer.write_u8(tag_id(value));
+    write_payload(buffer, value)
+}
+
+// fastnbt writes a named root, the name is dropped from its output
+pub fn write<T: Serialize>(buffer: &mut ByteBuffer, value: &T) -> Result<()> {
+    let bytes = fastnbt::to_bytes(value)?;
+    let name_length = u16::from_be_bytes([bytes[1], bytes[2]]) as usize;
+    buffer.write_u8(bytes[0]);
+    buffer.write_bytes(&bytes[3 + name_length..]);
+    Ok(())
+}
+
+fn write_string(buffer: &mut ByteBuffer, string: &str) -> Result<()> {
+    // java's modified utf-8
+    let bytes = cesu8::to_java_cesu8(string);
+    if bytes.len() > MAX_STRING_LENGTH {
+        return Err(ProtocolError::StringTooLong { length: bytes.len(), max: MAX_STRING_LENGTH });
+    }
+    buffer.write_u16(bytes.len() as u16);
+    buffer.write_bytes(&bytes);
+    Ok(())
+}
+
+fn write_payload(buffer: &mut ByteBuffer, value: &Value) -> Result<()> {
+    match value {
+        Value::Byte(value) => buffer.write_i8(*value),
+        Value::Short(value) => buffer.write_i16(*value),
+        Value::Int(value) => buffer.write_i32(*value),
+        Value::Long(value) => buffer.write_i64(*value),
+        Value::Float(value) => buffer.write_f32(*value),
+        Value::Double(value) => buffer.write_f64(*value),
+        Value::ByteArray(array) => {
+            buffer.write_i32(array.len() as i32);
+            array.iter().for_each(|value| buffer.write_i8(*value));
+        }
+        Value::String(string) => write_string(buffer, string)?,
+        Value::List(elements) => {
+            buffer.write_u8(elements.first().map_or(TAG_END, tag_id));
+            buffer.write_i32(elements.len() as i32);
+            for element in elements {
+                write_payload(buffer, element)?;
+            }
+        }
+        Value::Compound(entries) => {
+            for (name, value) in entries {
+                buffer.write_u8(tag_id(value));
+                write_string(buffer, name)?;
+                write_payload(buffer, value)?;
+            }
+            buffer.write_u8(TAG_END);
+        }
+        Value::IntArray(array) => {
+            buffer.write_i32(array.len() as i32);
+            array.iter().for_each(|value| buffer.write_i32(*value));
+        }
+        Value::LongArray(array) => {
+            buffer.write_i32(array.len() as i32);
+            array.iter().for_each(|value| buffer.write_i64(*value));
+        }
+    }
+    Ok(())
+}
+
+// for types whose serde layout is made for json, like text components. Booleans become bytes and nulls are left out
+pub fn from_json(json: &serde_json::Value) -> Option<Value> {
+    Some(match json {
+        serde_json::Value::Null => return None,
+        serde_json::Value::Bool(value) => Value::Byte(*value as i8),
+        serde_json::Value::Number(number) => match number.as_i64() {
+            Some(value) => match i32::try_from(value) {
+                Ok(value) => Value::Int(value),
+                Err(_) => Value::Long(value)
+            },
+            None => Value::Double(number.as_f64().unwrap_or_default())
+        },
+        serde_json::Value::String(string) => Value::String(string.clone()),
+        serde_json::Value::Array(elements) => Value::List(elements.iter().filter_map(from_json).collect()),
+        serde_json::Value::Object(entries) => Value::Compound(entries.iter()
+            .filter_map(|(name, value)| Some((name.clone(), from_json(value)?)))
+            .collect())
+    })
+}
+
+pub fn to_json(value: &Value) -> serde_json::Value {
+    match value {
+        Value::Byte(value) => (*value).into(),
+        Value::Short(value) => (*value).into(),
+        Value::Int(value) => (*value).into(),
+        Value::Long(value) => (*value).into(),
+        Value::Float(value) => (*value).into(),
+        Value::Double(value) => (*value).into(),
+        Value::String(string) => string.as_str().into(),
+        Value::ByteArray(array) => array.iter().copied().collect(),
+        Value::IntArray(array) => array.iter().copied().collect(),
+        Value::LongArray(array) => array.iter().copied().collect(),
+        Value::List(elements) => elements.iter().map(to_json).collect(),
+        Value::Compound(entries) => serde_json::Value::Object(entries.iter()
+            .map(|(name, value)| (name.clone(), to_json(value)))
+            .collect())
+    }
+}
+
+// reads a nameless root of any type, an end tag stands for no value
+pub fn read_tag(buffer: &mut ByteBuffer) -> Result<Option<Value>> {
+    match buffer.read_u8()? {
+        TAG_END => Ok(None),
+        tag => Ok(Some(read_payload(buffer, tag)?))
+    }
+}
+
+pub fn read_compound(buffer: &mut ByteBuffer) -> Result<Value> {
+    match buffer.read_u8()? {
+        TAG_COMPOUND => read_payload(buffer, TAG_COMPOUND),
+        tag => Err(ProtocolError::UnknownVariant { name: "nbt root tag", id: tag as u32 })
+    }
+}
+
+pub fn read<T: DeserializeOwned>(buffer: &mut ByteBuffer) -> Result<T> {
+    match read_tag(buffer)? {
+        Some(value) => Ok(fastnbt::from_value(&value)?),
+        None => Err(ProtocolError::InvalidNbt("missing root tag".to_string()))
+    }
+}
+
+fn read_string(buffer: &mut ByteBuffer) -> Result<String> {
+    let length = buffer.read_u16()? as usize;
+    let bytes = buffer.read_bytes(length)?;
+    cesu8::from_java_cesu8(&bytes)
+        .map(|string| string.into_owned())
+        .map_err(|_| ProtocolError::InvalidNbt("invalid modified utf-8 string".to_string()))
+}
+
+// array lengths are checked against what's left in the buffer before allocating
+fn read_length(buffer: &mut ByteBuffer, element_size: usize) -> Result<usize> {
+    let length = buffer.read_i32()?;
+    if length < 0 || length as usize * element_size > buffer.readabe_bytes() {
+        return Err(ProtocolError::InvalidNbt(format!("invalid array length {length}")));
+    }
+    Ok(length as usize)
+}
+
+// compounds and lists being read, kept on the heap so deeply nested data can't overflow the stack
+enum Nested {
+    List { tag: u8, remaining: usize, elements: Vec<Value> },
+    Compound { name: String, entries: HashMap<String, Value> }
+}
+
+impl Nested {
+    fn finish(self) -> Value {
+        match self {
+            Nested::List { elements, .. } => Value::List(elements),
+            Nested::Compound { entries, .. } => Value::Compound(entries)
+        }
+    }
+}
+
+fn read_payload(buffer: &mut ByteBuffer, tag: u8) -> Result<Value> {
+    let mut stack: Vec<Nested> = Vec::new();
+    let mut tag = tag;
+    loop {
+        let mut value = match tag {
+            TAG_LIST => {
+                let element_tag = buffer.read_u8()?;
+                let length = read_length(buffer, 0)?;
+                if element_tag == TAG_END && length > 0 {
+                    return Err(ProtocolError::InvalidNbt("list of end tags".to_string()));
+                }
+                stack.push(Nested::List { tag: element_tag, remaining: length, elements: Vec::new() });
+                None
+            }
+            TAG_COMPOUND => {
+                stack.push(Nested::Compound { name: String::new(), entries: HashMap::new() });
+                None
+            }
+            tag => Some(read_scalar(buffer, tag)?)
+        };
+        if stack.len() > MAX_DEPTH {
+            return Err(ProtocolError::InvalidNbt(format!("nested deeper than {MAX_DEPTH}")));
+        }
+        // hand the value to its parent, closing every container that's complete, until one needs another element
+        loop {
+            match stack.last_mut() {
+                None => return value.ok_or_else(|| ProtocolError::InvalidNbt("missing root tag".to_string())),
+                Some(Nested::List { tag: element_tag, remaining, elements }) => {
+                    elements.extend(value.take());
+                    if *remaining > 0 {
+                        *remaining -= 1;
+                        tag = *element_tag;
+                        break;
+                    }
+                }
+                Some(Nested::Compound { name, entries }) => {
+                    if let Some(value) = value.take() {
+                        entries.insert(std::mem::take(name), value);
+                    }
+                    let next = buffer.read_u8()?;
+                    if next != TAG_END {
+                        *name = read_string(buffer)?;
+                        tag = next;
+                        break;
+                    }
+                }
+            }
+            value = stack.pop().map(Nested::finish);
+        }
+    }
+}
+
+fn read_scalar(buffer: &mut ByteBuffer, tag: u8) -> Result<Value> {
+    Ok(match tag {
+        TAG_BYTE => Value::Byte(buffer.read_i8()?),
+        TAG_SHORT => Value::Short(buffer.read_i16()?),
+        TAG_INT => Value::Int(buffer.read_i32()?),
+        TAG_LONG => Value::Long(buffer.read_i64()?),
+        TAG_FLOAT => Value::Float(buffer.read_f32()?),
+        TAG_DOUBLE => Value::Double(buffer.read_f64()?),
+        TAG_BYTE_ARRAY => {
+            let length = read_length(buffer, 1)?;
+            Value::ByteArray(ByteArray::new(buffer.read_bytes(length)?.into_iter().map(|byte| byte as i8).collect()))
+        }
+        TAG_STRING => Value::String(read_string(buffer)?),
+        TAG_INT_ARRAY => {
+            let length = read_length(buffer, 4)?;
+            let mut values = Vec::with_capacity(length);
+            for _ in 0..length {
+                values.push(buffer.read_i32()?);
+            }
+            Value::IntArray(IntArray::new(values))
+        }
+        TAG_LONG_ARRAY => {
+            let length = read_length(buffer, 8)?;
+            let mut values = Vec::with_capacity(length);
+            for _ in 0..length {
+                values.push(buffer.read_i64()?);
+            }
+            Value::LongArray(LongArray::new(values))
+        }
+        tag => return Err(ProtocolError::UnknownVariant { name: "nbt tag", id: tag as u32 })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use fastnbt::nbt;
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Effects {
+        fog_color: i32,
+        music: Option<String>,
+        particles: Vec<f32>
+    }
+
+    #[test]
+    fn matches_named_root_without_the_name() {
+        let value = nbt!({ "name": "minecraft:plains", "id": 3, "element": { "temperature": 0.8f32 } });
+        let mut buffer = ByteBuffer::new();
+        write_tag(&mut buffer, &value).unwrap();
+        let named = fastnbt::to_bytes(&value).unwrap();
+        // the named format has a 0 length name after the type id
+        assert_eq!(named[..3], [TAG_COMPOUND, 0, 0]);
+        assert_eq!(buffer.as_bytes()[0], TAG_COMPOUND);
+        assert_eq!(buffer.as_bytes().len(), named.len() - 2);
+        assert_eq!(read_compound(&mut buffer).unwrap(), value);
+        assert_eq!(buffer.readabe_bytes(), 0);
+    }
+
+    #[test]
+    fn every_tag_round_trips() {
+        let value = nbt!({
+            "byte": 1i8, "short": 2i16, "int": 3, "long": 4i64, "float": 0.5f32, "double": 0.25,
+            "string": "\0 and 😀", "list": [1, 2], "empty": [], "nested": [{ "a": [[1i8]] }],
+            "bytes": [B; 1, 2], "ints": [I; 3], "longs": [L; 4, 5]
+        });
+        let mut buffer = ByteBuffer::new();
+        write_tag(&mut buffer, &value).unwrap();
+        assert_eq!(read_tag(&mut buffer).unwrap(), Some(value));
+
+        // roots don't have to be compounds
+        write_tag(&mut buffer, &Value::String("text".to_string())).unwrap();
+        assert_eq!(read_tag(&mut buffer).unwrap(), Some(Value::String("text".to_string())));
+        assert!(matches!(read_compound(&mut ByteBuffer::from_bytes(&[TAG_STRING, 0, 0])), Err(ProtocolError::UnknownVariant { .. })));
+        assert_eq!(read_tag(&mut ByteBuffer::from_bytes(&[TAG_END])).unwrap(), None);
+    }
+
+    #[test]
+    fn modified_utf8_strings() {
+        let mut buffer = ByteBuffer::new();
+        write_tag(&mut buffer, &Value::String("\0".to_string())).unwrap();
+        assert_eq!(buffer.as_bytes(), [TAG_STRING, 0, 2, 0xc0, 0x80]);
+
+        // the length has to fit an unsigned short, also for compound names
+        let longest = "a".repeat(MAX_STRING_LENGTH);
+        assert!(write_tag(&mut ByteBuffer::new(), &Value::String(longest.clone())).is_ok());
+        let too_long = Value::String(longest.clone() + "a");
+        assert!(matches!(write_tag(&mut ByteBuffer::new(), &too_long), Err(ProtocolError::StringTooLong { length: 65536, .. })));
+        let name = Value::Compound([(longest + "a", Value::Byte(0))].into_iter().collect());
+        assert!(write_tag(&mut ByteBuffer::new(), &name).is_err());
+    }
+
+    #[test]
+    fn serde_types() {
+        let effects = Effects { fog_color: 0xc0d8ff, music: None, particles: vec![0.1, 0.2] };
+        let mut buffer = ByteBuffer::new();
+        write(&mut buffer, &effects).unwrap();
+        assert_eq!(read::<Effects>(&mut buffer).unwrap(), effects);
+    }
+
+    #[test]
+    fn rejects_malformed_data() {
+        // compound holding a byte array that claims more bytes than there are
+        let mut buffer = ByteBuffer::from_bytes(&[TAG_COMPOUND, TAG_BYTE_ARRAY, 0, 1, b'a', 0x7f, 0xff, 0xff, 0xff]);
+        assert!(matches!(read_compound(&mut buffer), Err(ProtocolError::InvalidNbt(_))));
+
+        let mut deep = ByteBuffer::new();
+        deep.write_u8(TAG_LIST);
+        for _ in 0..MAX_DEPTH + 1 {
+            deep.write_u8(TAG_LIST);
+            deep.write_i32(1);
+        }
+        deep.write_u8(TAG_END);
+        deep.write_i32(0);
+        assert!(matches!(read_tag(&mut deep), Err(ProtocolError::InvalidNbt(_))));
+    }
+}
@@ -0,0 +1,284 @@
+use bytebuffer::ByteBuffer;
+use fastnbt::Value;
+
+use crate::codec::{McDecode, McEncode};
+use crate::error::Result;
+use crate::state::ConnectionState;
+use crate::text::TextComponent;
+use crate::types::Identifier;
+use crate::version::{PacketIds, ProtocolVersion};
+use super::{packet_enum, Packet};
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct ClientboundPluginMessage {
+    pub channel: String,
+    pub data: Vec<u8>
+}
+
+impl Packet for ClientboundPluginMessage {
+    const IDS: PacketIds = PacketIds::new(0x00, 0x00, 0x01);
+}
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct RegistryData {
+    pub registry_codec: Value
+}
+
+impl Packet for RegistryData {
+    const IDS: PacketIds = PacketIds::until_1_20_4(0x05);
+}
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct RegistryEntry {
+    pub id: String,
+    #[mc(optional)]
+    pub data: Option<Value>
+}
+
+// Registry Data since 1.20.5, sent once per registry instead of as a single codec
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct RegistryEntries {
+    pub registry_id: String,
+    #[mc(length_prefixed)]
+    pub entries: Vec<RegistryEntry>
+}
+
+impl Packet for RegistryEntries {
+    const IDS: PacketIds = PacketIds::since_1_20_6(0x07);
+}
+
+impl RegistryEntries {
+    // splits a pre 1.20.5 registry codec, entries keep the order of their ids
+    pub fn from_codec(registry_codec: &Value) -> Vec<RegistryEntries> {
+        let Value::Compound(registries) = registry_codec else {
+            return vec![];
+        };
+        registries.iter()
+            .filter_map(|(registry_id, registry)| {
+                let Value::Compound(registry) = registry else {
+                    return None;
+                };
+                let Some(Value::List(values)) = registry.get("value") else {
+                    return None;
+                };
+                let entries = values.iter()
+                    .filter_map(|entry| {
+                        let Value::Compound(entry) = entry else {
+                            return None;
+                        };
+                        let Some(Value::String(name)) = entry.get("name") else {
+                            return None;
+                        };
+                        Some(RegistryEntry { id: name.clone(), data: entry.get("element").cloned() })
+                    })
+                    .collect();
+                Some(RegistryEntries { registry_id: registry_id.clone(), entries })
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct FinishConfiguration;
+
+impl Packet for FinishConfiguration {
+    const IDS: PacketIds = PacketIds::new(0x02, 0x02, 0x03);
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigurationDisconnect {
+    pub reason: TextComponent
+}
+
+impl McEncode for ConfigurationDisconnect {
+    fn encode(&self, buffer: &mut ByteBuffer) -> Result<()> {
+        self.reason.write_nbt(buffer)
+    }
+}
+
+impl McDecode for ConfigurationDisconnect {
+    fn decode(buffer: &mut ByteBuffer) -> Result<Self> {
+        Ok(ConfigurationDisconnect { reason: TextComponent::read_nbt(buffer)? })
+    }
+}
+
+impl Packet for ConfigurationDisconnect {
+    const IDS: PacketIds = PacketIds::new(0x01, 0x01, 0x02);
+
+    fn encode_for(&self, version: ProtocolVersion, buffer: &mut ByteBuffer) -> Result<()> {
+        self.reason.write_for(version, buffer)
+    }
+
+    fn decode_for(version: ProtocolVersion, buffer: &mut ByteBuffer) -> Result<Self> {
+        Ok(ConfigurationDisconnect { reason: TextComponent::read_for(version, buffer)? })
+    }
+}
+
+// experimental features the client should turn on, `minecraft:vanilla` for none
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct FeatureFlags {
+    #[mc(length_prefixed)]
+    pub feature_flags: Vec<Identifier>
+}
+
+impl Packet for FeatureFlags {
+    const IDS: PacketIds = PacketIds::new(0x07, 0x08, 0x0c);
+}
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct Tag {
+    pub name: Identifier,
+    // registry ids of the tagged entries
+    #[mc(length_prefixed, varint)]
+    pub entries: Vec<u32>
+}
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct RegistryTags {
+    pub registry: Identifier,
+    #[mc(length_prefixed)]
+    pub tags: Vec<Tag>
+}
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct UpdateTags {
+    #[mc(length_prefixed)]
+    pub registries: Vec<RegistryTags>
+}
+
+impl Packet for UpdateTags {
+    const IDS: PacketIds = PacketIds::new(0x08, 0x09, 0x0d);
+}
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct KnownPack {
+    pub namespace: String,
+    pub id: String,
+    pub version: String
+}
+
+// data packs whose registry entries the client may already have, since 1.20.5
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct ClientboundKnownPacks {
+    #[mc(length_prefixed)]
+    pub known_packs: Vec<KnownPack>
+}
+
+impl Packet for ClientboundKnownPacks {
+    const IDS: PacketIds = PacketIds::since_1_20_6(0x0e);
+}
+
+// the packs from Clientbound Known Packs the client has too
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct ServerboundKnownPacks {
+    #[mc(length_prefixed)]
+    pub known_packs: Vec<KnownPack>
+}
+
+impl Packet for ServerboundKnownPacks {
+    const IDS: PacketIds = PacketIds::since_1_20_6(0x07);
+}
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct AcknowledgeFinishConfiguration;
+
+impl Packet for AcknowledgeFinishConfiguration {
+    const IDS: PacketIds = PacketIds::new(0x02, 0x02, 0x03);
+}
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct ClientInformation {
+    pub locale: String,
+    pub view_distance: i8,
+    #[mc(varint)]
+    pub chat_mode: u32,
+    pub chat_colors: bool,
+    pub displayed_skin_parts: u8,
+    #[mc(varint)]
+    pub main_hand: u32,
+    pub enable_text_filtering: bool,
+    pub allow_server_listings: bool
+}
+
+impl Packet for ClientInformation {
+    const IDS: PacketIds = PacketIds::same(0x00);
+}
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct ServerboundPluginMessage {
+    pub channel: String,
+    pub data: Vec<u8>
+}
+
+impl Packet for ServerboundPluginMessage {
+    const IDS: PacketIds = PacketIds::new(0x01, 0x01, 0x02);
+}
+
+packet_enum!(ServerboundConfigurationPacket, ConnectionState::Configuration, {
+    ClientInformation,
+    ServerboundPluginMessage,
+    AcknowledgeFinishConfiguration,
+    ServerboundKnownPacks
+});
+
+packet_enum!(ClientboundConfigurationPacket, ConnectionState::Configuration, {
+    ClientboundPluginMessage,
+    ConfigurationDisconnect,
+    FinishConfiguration,
+    RegistryData,
+    RegistryEntries,
+    FeatureFlags,
+    UpdateTags,
+    ClientboundKnownPacks
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packets::tests::{assert_round_trip, assert_round_trip_for};
+    use crate::registry::REGISTRY;
+    use crate::version::ProtocolVersion;
+
+    #[test]
+    fn configuration_packets_round_trip() {
+        assert_round_trip(RegistryData { registry_codec: REGISTRY.to_codec(ProtocolVersion::V1_20_4).unwrap() });
+        assert_round_trip(FinishConfiguration);
+        assert_round_trip(AcknowledgeFinishConfiguration);
+        for version in ProtocolVersion::SUPPORTED {
+            assert_round_trip_for(version, ConfigurationDisconnect { reason: TextComponent::translate("disconnect.timeout") });
+        }
+        assert_round_trip(ClientInformation {
+            locale: "en_us".to_string(),
+            view_distance: 12,
+            chat_mode: 0,
+            chat_colors: true,
+            displayed_skin_parts: 0x7f,
+            main_hand: 1,
+            enable_text_filtering: false,
+            allow_server_listings: true
+        });
+        assert_round_trip(ServerboundPluginMessage { channel: "minecraft:brand".to_string(), data: b"\x07vanilla".to_vec() });
+        assert_round_trip(ClientboundPluginMessage { channel: "minecraft:brand".to_string(), data: b"\x07vanilla".to_vec() });
+        assert_round_trip(FeatureFlags { feature_flags: vec![Identifier::minecraft("vanilla").unwrap()] });
+        assert_round_trip(UpdateTags {
+            registries: vec![RegistryTags {
+                registry: Identifier::minecraft("block").unwrap(),
+                tags: vec![Tag { name: Identifier::minecraft("logs").unwrap(), entries: vec![46, 300] }]
+            }]
+        });
+        let known_packs = vec![KnownPack { namespace: "minecraft".to_string(), id: "core".to_string(), version: "1.20.6".to_string() }];
+        assert_round_trip_for(ProtocolVersion::V1_20_6, ClientboundKnownPacks { known_packs: known_packs.clone() });
+        assert_round_trip_for(ProtocolVersion::V1_20_6, ServerboundKnownPacks { known_packs });
+    }
+
+    #[test]
+    fn splits_registry_codec() {
+        let registries = RegistryEntries::from_codec(&REGISTRY.to_codec(ProtocolVersion::V1_20_6).unwrap());
+        let dimension_types = registries.iter().find(|registry| registry.registry_id == "minecraft:dimension_type").unwrap();
+        assert_eq!(dimension_types.entries[0].id, "minecraft:overworld");
+        assert!(dimension_types.entries[0].data.is_some());
+        for registry in registries {
+            assert_round_trip_for(ProtocolVersion::V1_20_6, registry);
+        }
+    }
+}
@@ -0,0 +1,141 @@
+// packets generated by build.rs from protocol/<version>/protocol.json. The hand-written packets stay the ones
+// the server uses, the tests below check their ids against these
+include!(concat!(env!("OUT_DIR"), "/packets.rs"));
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::codec::MinecraftReadTypes;
+    use crate::packets::tests::assert_round_trip_for;
+    use crate::packets::{configuration as c, handshaking as h, login as l, play as p, status as s};
+    use crate::packets::{encode_packet, Packet};
+    use crate::version::{PacketIds, ProtocolVersion};
+    use super::*;
+
+    // every hand-written packet has to be named after its vendored packet, and every vendored packet needs
+    // a hand-written one with the same ids or has to be listed as unimplemented.
+    // A packet split across versions lists each hand-written part
+    fn assert_ids_match(
+        generated: &[(&str, PacketIds)],
+        handwritten: &[(&str, PacketIds)],
+        names: &[(&str, &[&str])],
+        unimplemented: &[&str]
+    ) {
+        for (packet, _) in handwritten {
+            assert!(names.iter().any(|(_, parts)| parts.contains(packet)), "{packet} has no vendored packet");
+        }
+        for (name, _) in names {
+            assert!(generated.iter().any(|(other, _)| other == name), "{name} isn't in the vendored protocol");
+        }
+        for (name, ids) in generated {
+            if unimplemented.contains(name) {
+                continue;
+            }
+            let (_, parts) = names.iter().find(|(other, _)| other == name)
+                .unwrap_or_else(|| panic!("{name} has no hand-written packet"));
+            for version in ProtocolVersion::SUPPORTED {
+                let handwritten = parts.iter()
+                    .map(|part| handwritten.iter().find(|(packet, _)| packet == part).unwrap_or_else(|| panic!("unknown packet {part}")).1)
+                    .find_map(|part| part.get(version));
+                assert_eq!(handwritten, ids.get(version), "{name} in {}", version.name());
+            }
+        }
+    }
+
+    #[test]
+    fn ids_follow_each_version() {
+        for version in ProtocolVersion::SUPPORTED {
+            assert_eq!(play::serverbound::KeepAlive::IDS.get(version), p::ServerboundKeepAlive::IDS.get(version));
+            assert_eq!(play::clientbound::UpdateViewPosition::IDS.get(version), p::SetCenterChunk::IDS.get(version));
+            assert_round_trip_for(version, play::serverbound::KeepAlive { keep_alive_id: 42 });
+        }
+        assert_eq!(login::clientbound::SuccessV1_20_2::IDS.get(ProtocolVersion::V1_20_6), None);
+    }
+
+    #[test]
+    fn handwritten_ids_match_vendored_protocol() {
+        assert_ids_match(handshaking::serverbound::PACKET_IDS, h::ServerboundHandshakingPacket::PACKETS, &[
+            ("set_protocol", &["Handshake"])
+        ], &["legacy_server_list_ping"]);
+        assert_ids_match(status::clientbound::PACKET_IDS, s::ClientboundStatusPacket::PACKETS, &[
+            ("server_info", &["StatusResponse"]),
+            ("ping", &["PingResponse"])
+        ], &[]);
+        assert_ids_match(status::serverbound::PACKET_IDS, s::ServerboundStatusPacket::PACKETS, &[
+            ("ping_start", &["StatusRequest"]),
+            ("ping", &["PingRequest"])
+        ], &[]);
+        assert_ids_match(login::clientbound::PACKET_IDS, l::ClientboundLoginPacket::PACKETS, &[
+            ("disconnect", &["LoginDisconnect"]),
+            ("encryption_begin", &["EncryptionRequest"]),
+            ("success", &["LoginSuccess"]),
+            ("compress", &["SetCompression"]),
+            ("login_plugin_request", &["LoginPluginRequest"])
+        ], &["cookie_request"]);
+        assert_ids_match(login::serverbound::PACKET_IDS, l::ServerboundLoginPacket::PACKETS, &[
+            ("login_start", &["LoginStart"]),
+            ("encryption_begin", &["EncryptionResponse"]),
+            ("login_plugin_response", &["LoginPluginResponse"]),
+            ("login_acknowledged", &["LoginAcknowledged"])
+        ], &["cookie_response"]);
+        assert_ids_match(configuration::clientbound::PACKET_IDS, c::ClientboundConfigurationPacket::PACKETS, &[
+            ("custom_payload", &["ClientboundPluginMessage"]),
+            ("disconnect", &["ConfigurationDisconnect"]),
+            ("finish_configuration", &["FinishConfiguration"]),
+            ("registry_data", &["RegistryData", "RegistryEntries"]),
+            ("feature_flags", &["FeatureFlags"]),
+            ("tags", &["UpdateTags"]),
+            ("select_known_packs", &["ClientboundKnownPacks"])
+        ], &["keep_alive", "ping", "reset_chat"]);
+        assert_ids_match(configuration::serverbound::PACKET_IDS, c::ServerboundConfigurationPacket::PACKETS, &[
+            ("settings", &["ClientInformation"]),
+            ("custom_payload", &["ServerboundPluginMessage"]),
+            ("finish_configuration", &["AcknowledgeFinishConfiguration"]),
+            ("select_known_packs", &["ServerboundKnownPacks"])
+        ], &["keep_alive", "pong"]);
+        assert_ids_match(play::clientbound::PACKET_IDS, p::ClientboundPlayPacket::PACKETS, &[
+            ("spawn_entity", &["SpawnEntity"]),
+            ("block_change", &["BlockUpdate"]),
+            ("kick_disconnect", &["PlayDisconnect"]),
+            ("game_state_change", &["GameEvent"]),
+            ("keep_alive", &["ClientboundKeepAlive"]),
+            ("map_chunk", &["ChunkDataAndUpdateLight"]),
+            ("login", &["LoginPlay"]),
+            ("position", &["SynchronizePlayerPosition"]),
+            ("camera", &["SetCamera"]),
+            ("update_view_position", &["SetCenterChunk"]),
+            ("entity_metadata", &["SetEntityMetadata"])
+        ], &[]);
+        assert_ids_match(play::serverbound::PACKET_IDS, p::ServerboundPlayPacket::PACKETS, &[
+            ("teleport_confirm", &["ConfirmTeleportation"]),
+            ("keep_alive", &["ServerboundKeepAlive"]),
+            ("position", &["SetPlayerPosition"]),
+            ("position_look", &["SetPlayerPositionAndRotation"]),
+            ("look", &["SetPlayerRotation"]),
+            ("flying", &["SetPlayerOnGround"])
+        ], &[]);
+    }
+
+    #[test]
+    fn changed_layouts_match_handwritten_packets() {
+        let packet = login::clientbound::SuccessV1_20_6 {
+            uuid: Uuid::new_v4(),
+            username: "Notch".to_string(),
+            properties: vec![login::clientbound::SuccessV1_20_6Properties {
+                name: "textures".to_string(),
+                value: "e30=".to_string(),
+                signature: None
+            }],
+            strict_error_handling: true
+        };
+        assert_round_trip_for(ProtocolVersion::V1_20_6, packet.clone());
+
+        let mut buffer = encode_packet(ProtocolVersion::V1_20_6, &packet).unwrap();
+        buffer.read_var_int().unwrap();
+        let decoded = l::LoginSuccess::decode_for(ProtocolVersion::V1_20_6, &mut buffer).unwrap();
+        assert_eq!(decoded.uuid, packet.uuid);
+        assert_eq!(decoded.properties[0].value, "e30=");
+        assert!(decoded.strict_error_handling);
+    }
+}
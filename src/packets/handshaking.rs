@@ -0,0 +1,38 @@
+use crate::codec::{McDecode, McEncode};
+use crate::state::ConnectionState;
+use crate::version::PacketIds;
+use super::{packet_enum, Packet};
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct Handshake {
+    #[mc(varint)]
+    pub protocol_version: u32,
+    pub server_address: String,
+    pub server_port: u16,
+    #[mc(varint)]
+    pub next_state: u32
+}
+
+impl Packet for Handshake {
+    const IDS: PacketIds = PacketIds::same(0x00);
+}
+
+packet_enum!(ServerboundHandshakingPacket, ConnectionState::Handshaking, {
+    Handshake
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packets::tests::assert_round_trip;
+
+    #[test]
+    fn handshake_round_trip() {
+        assert_round_trip(Handshake {
+            protocol_version: 765,
+            server_address: "localhost".to_string(),
+            server_port: 25565,
+            next_state: 2
+        });
+    }
+}
@@ -0,0 +1,238 @@
+use bytebuffer::ByteBuffer;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::codec::{McDecode, McEncode};
+use crate::error::Result;
+use crate::state::ConnectionState;
+use crate::text::TextComponent;
+use crate::types::Identifier;
+use crate::version::{PacketIds, ProtocolVersion};
+use super::{packet_enum, Packet};
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct LoginStart {
+    pub name: String,
+    pub uuid: Uuid
+}
+
+impl Packet for LoginStart {
+    const IDS: PacketIds = PacketIds::same(0x00);
+}
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct EncryptionResponse {
+    #[mc(length_prefixed)]
+    pub shared_secret: Vec<u8>,
+    #[mc(length_prefixed)]
+    pub verify_token: Vec<u8>
+}
+
+impl Packet for EncryptionResponse {
+    const IDS: PacketIds = PacketIds::same(0x01);
+}
+
+// answers a Login Plugin Request with the same message id, vanilla clients answer every request unsuccessfully
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct LoginPluginResponse {
+    #[mc(varint)]
+    pub message_id: u32,
+    #[mc(optional)]
+    pub data: Option<Vec<u8>>
+}
+
+impl Packet for LoginPluginResponse {
+    const IDS: PacketIds = PacketIds::same(0x02);
+}
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct LoginAcknowledged;
+
+impl Packet for LoginAcknowledged {
+    const IDS: PacketIds = PacketIds::same(0x03);
+}
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct EncryptionRequest {
+    // always empty since 1.7
+    pub server_id: String,
+    #[mc(length_prefixed)]
+    pub public_key: Vec<u8>,
+    #[mc(length_prefixed)]
+    pub verify_token: Vec<u8>,
+    // since 1.20.5, whether the client has to join through the session service
+    #[mc(skip)]
+    pub should_authenticate: bool
+}
+
+impl Packet for EncryptionRequest {
+    const IDS: PacketIds = PacketIds::same(0x01);
+
+    fn encode_for(&self, version: ProtocolVersion, buffer: &mut ByteBuffer) -> Result<()> {
+        self.encode(buffer)?;
+        if version >= ProtocolVersion::V1_20_6 {
+            self.should_authenticate.encode(buffer)?;
+        }
+        Ok(())
+    }
+
+    fn decode_for(version: ProtocolVersion, buffer: &mut ByteBuffer) -> Result<Self> {
+        let mut encryption_request = Self::decode(buffer)?;
+        if version >= ProtocolVersion::V1_20_6 {
+            encryption_request.should_authenticate = bool::decode(buffer)?;
+        }
+        Ok(encryption_request)
+    }
+}
+
+// game profile property like the skin textures, also the json layout of the session service
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode, Serialize, Deserialize)]
+pub struct Property {
+    pub name: String,
+    pub value: String,
+    #[mc(optional)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>
+}
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct LoginSuccess {
+    pub uuid: Uuid,
+    pub username: String,
+    #[mc(length_prefixed)]
+    pub properties: Vec<Property>,
+    // since 1.20.5
+    #[mc(skip)]
+    pub strict_error_handling: bool
+}
+
+impl Packet for LoginSuccess {
+    const IDS: PacketIds = PacketIds::same(0x02);
+
+    fn encode_for(&self, version: ProtocolVersion, buffer: &mut ByteBuffer) -> Result<()> {
+        self.encode(buffer)?;
+        if version >= ProtocolVersion::V1_20_6 {
+            self.strict_error_handling.encode(buffer)?;
+        }
+        Ok(())
+    }
+
+    fn decode_for(version: ProtocolVersion, buffer: &mut ByteBuffer) -> Result<Self> {
+        let mut login_success = Self::decode(buffer)?;
+        if version >= ProtocolVersion::V1_20_6 {
+            login_success.strict_error_handling = bool::decode(buffer)?;
+        }
+        Ok(login_success)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct SetCompression {
+    #[mc(varint)]
+    pub threshold: i32
+}
+
+impl Packet for SetCompression {
+    const IDS: PacketIds = PacketIds::same(0x03);
+}
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct LoginPluginRequest {
+    #[mc(varint)]
+    pub message_id: u32,
+    pub channel: Identifier,
+    pub data: Vec<u8>
+}
+
+impl Packet for LoginPluginRequest {
+    const IDS: PacketIds = PacketIds::same(0x04);
+}
+
+// the reason is always json here, even in versions that send nbt in play
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoginDisconnect {
+    pub reason: TextComponent
+}
+
+impl McEncode for LoginDisconnect {
+    fn encode(&self, buffer: &mut ByteBuffer) -> Result<()> {
+        self.reason.write_json(buffer);
+        Ok(())
+    }
+}
+
+impl McDecode for LoginDisconnect {
+    fn decode(buffer: &mut ByteBuffer) -> Result<Self> {
+        Ok(LoginDisconnect { reason: TextComponent::read_json(buffer)? })
+    }
+}
+
+impl Packet for LoginDisconnect {
+    const IDS: PacketIds = PacketIds::same(0x00);
+}
+
+packet_enum!(ServerboundLoginPacket, ConnectionState::Login, {
+    LoginStart,
+    EncryptionResponse,
+    LoginPluginResponse,
+    LoginAcknowledged
+});
+
+packet_enum!(ClientboundLoginPacket, ConnectionState::Login, {
+    LoginDisconnect,
+    EncryptionRequest,
+    LoginSuccess,
+    SetCompression,
+    LoginPluginRequest
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packets::encode_packet;
+    use crate::packets::tests::{assert_round_trip, assert_round_trip_for};
+
+    #[test]
+    fn login_packets_round_trip() {
+        assert_round_trip(LoginStart { name: "Notch".to_string(), uuid: Uuid::new_v4() });
+        assert_round_trip(EncryptionRequest {
+            server_id: String::new(),
+            public_key: vec![0x30, 0x81, 0x9f],
+            verify_token: vec![1, 2, 3, 4],
+            should_authenticate: false
+        });
+        assert_round_trip(EncryptionResponse { shared_secret: vec![7; 128], verify_token: vec![9; 128] });
+        assert_round_trip(LoginAcknowledged);
+        assert_round_trip(LoginPluginRequest { message_id: 1, channel: Identifier::parse("velocity:player_info").unwrap(), data: vec![1] });
+        assert_round_trip(LoginPluginResponse { message_id: 1, data: Some(vec![0; 40]) });
+        assert_round_trip(LoginPluginResponse { message_id: 2, data: None });
+        assert_round_trip(SetCompression { threshold: 256 });
+        assert_round_trip(SetCompression { threshold: -1 });
+        assert_round_trip(LoginDisconnect { reason: TextComponent::text("Server is full").color("red") });
+        assert_round_trip(LoginSuccess {
+            uuid: Uuid::new_v4(),
+            username: "Notch".to_string(),
+            properties: vec![
+                Property { name: "textures".to_string(), value: "e30=".to_string(), signature: None },
+                Property { name: "textures".to_string(), value: "e30=".to_string(), signature: Some("c2ln".to_string()) }
+            ],
+            strict_error_handling: false
+        });
+    }
+
+    #[test]
+    fn login_layouts_per_version() {
+        let login_success = LoginSuccess { uuid: Uuid::nil(), username: "Notch".to_string(), properties: vec![], strict_error_handling: true };
+        assert_round_trip_for(ProtocolVersion::V1_20_6, login_success.clone());
+        let older = encode_packet(ProtocolVersion::V1_20_4, &login_success).unwrap();
+        let latest = encode_packet(ProtocolVersion::V1_20_6, &login_success).unwrap();
+        assert_eq!(older.len() + 1, latest.len());
+
+        let encryption_request = EncryptionRequest { server_id: String::new(), public_key: vec![1], verify_token: vec![2], should_authenticate: true };
+        assert_round_trip_for(ProtocolVersion::V1_20_6, encryption_request.clone());
+        let older = encode_packet(ProtocolVersion::V1_20_4, &encryption_request).unwrap();
+        let latest = encode_packet(ProtocolVersion::V1_20_6, &encryption_request).unwrap();
+        assert_eq!(older.as_bytes(), [0x01, 0, 1, 1, 1, 2]);
+        assert_eq!(latest.as_bytes(), [0x01, 0, 1, 1, 1, 2, 1]);
+    }
+}
@@ -0,0 +1,137 @@
+use bytebuffer::ByteBuffer;
+use tokio::io::AsyncWriteExt;
+
+use crate::codec::{McDecode, McEncode, MinecraftWriteTypes};
+use crate::error::{ProtocolError, Result};
+use crate::framing::write_packet;
+use crate::version::{PacketIds, ProtocolVersion};
+
+pub mod configuration;
+pub mod generated;
+pub mod handshaking;
+pub mod login;
+pub mod play;
+pub mod status;
+
+pub trait Packet: McEncode + McDecode {
+    const IDS: PacketIds;
+
+    // McEncode/McDecode are the 1.20.4 layout, packets whose fields changed in other versions override these
+    fn encode_for(&self, _version: ProtocolVersion, buffer: &mut ByteBuffer) -> Result<()> {
+        self.encode(buffer)
+    }
+
+    fn decode_for(_version: ProtocolVersion, buffer: &mut ByteBuffer) -> Result<Self> {
+        Self::decode(buffer)
+    }
+}
+
+// generates a per-state enum over the given packet structs, dispatching on the packet id
+macro_rules! packet_enum {
+    ($name:ident, $state:expr, { $($variant:ident),* $(,)? }) => {
+        #[derive(Debug, Clone, PartialEq)]
+        pub enum $name {
+            $($variant($variant)),*
+        }
+
+        impl $name {
+            // every variant's struct name and ids
+            pub const PACKETS: &'static [(&'static str, $crate::version::PacketIds)] =
+                &[$((stringify!($variant), <$variant as $crate::packets::Packet>::IDS)),*];
+
+            pub fn id(&self, version: $crate::version::ProtocolVersion) -> Option<u32> {
+                match self {
+                    $(Self::$variant(_) => <$variant as $crate::packets::Packet>::IDS.get(version)),*
+                }
+            }
+
+            pub fn contains(version: $crate::version::ProtocolVersion, id: u32) -> bool {
+                $(<$variant as $crate::packets::Packet>::IDS.get(version) == Some(id))||*
+            }
+
+            pub fn encode(&self, version: $crate::version::ProtocolVersion, buffer: &mut bytebuffer::ByteBuffer) -> $crate::error::Result<()> {
+                match self {
+                    $(Self::$variant(packet) => $crate::packets::Packet::encode_for(packet, version, buffer)),*
+                }
+            }
+
+            // the packet with its id in front, like encode_packet
+            pub fn encode_packet(&self, version: $crate::version::ProtocolVersion) -> $crate::error::Result<bytebuffer::ByteBuffer> {
+                match self {
+                    $(Self::$variant(packet) => $crate::packets::encode_packet(version, packet)),*
+                }
+            }
+
+            pub fn decode(version: $crate::version::ProtocolVersion, id: u32, buffer: &mut bytebuffer::ByteBuffer) -> $crate::error::Result<Self> {
+                $(
+                    if <$variant as $crate::packets::Packet>::IDS.get(version) == Some(id) {
+                        return Ok(Self::$variant(<$variant as $crate::packets::Packet>::decode_for(version, buffer)?));
+                    }
+                )*
+                Err($crate::error::ProtocolError::UnknownPacket { state: $state, id })
+            }
+        }
+
+        $(
+            impl From<$variant> for $name {
+                fn from(packet: $variant) -> Self {
+                    Self::$variant(packet)
+                }
+            }
+        )*
+    };
+}
+
+pub(crate) use packet_enum;
+
+pub fn allocate_buffer() -> ByteBuffer {
+    let mut content_write_buffer: ByteBuffer = ByteBuffer::from_vec(vec![0;0]);
+    content_write_buffer.set_wpos(0);
+    content_write_buffer
+}
+
+pub fn prepare_packet_buffer(packet_id: u32) -> ByteBuffer {
+    let mut buffer = allocate_buffer();
+    buffer.write_var_int(packet_id);
+    buffer
+}
+
+pub fn encode_packet<P: Packet>(version: ProtocolVersion, packet: &P) -> Result<ByteBuffer> {
+    let Some(id) = P::IDS.get(version) else {
+        return Err(ProtocolError::UnsupportedPacket { packet: std::any::type_name::<P>(), version });
+    };
+    let mut buffer = prepare_packet_buffer(id);
+    packet.encode_for(version, &mut buffer)?;
+    Ok(buffer)
+}
+
+pub async fn send_packet<T, P>(socket: &mut T, version: ProtocolVersion, packet: &P) -> Result<()>
+where T: AsyncWriteExt + Unpin, P: Packet
+{
+    write_packet(socket, &mut encode_packet(version, packet)?).await
+}
+
+pub async fn write_block<T>(socket: &mut T, version: ProtocolVersion, x: i32, y: i16, z: i32, block_id: u32) -> Result<()>
+where T: AsyncWriteExt + Unpin
+{
+    send_packet(socket, version, &play::BlockUpdate { x, y, z, block_id }).await
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::fmt::Debug;
+    use super::*;
+    use crate::codec::MinecraftReadTypes;
+
+    pub(crate) fn assert_round_trip<P: Packet + PartialEq + Debug>(packet: P) {
+        assert_round_trip_for(ProtocolVersion::default(), packet);
+    }
+
+    pub(crate) fn assert_round_trip_for<P: Packet + PartialEq + Debug>(version: ProtocolVersion, packet: P) {
+        let mut buffer = encode_packet(version, &packet).unwrap();
+        assert_eq!(Some(buffer.read_var_int().unwrap()), P::IDS.get(version));
+        let decoded = P::decode_for(version, &mut buffer).unwrap();
+        assert_eq!(buffer.readabe_bytes(), 0, "packet has trailing bytes");
+        assert_eq!(decoded, packet);
+    }
+}
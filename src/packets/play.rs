@@ -0,0 +1,577 @@
+use crate::error::Result;
+use bytebuffer::ByteBuffer;
+use fastnbt::Value;
+use uuid::Uuid;
+
+use crate::codec::{McDecode, McEncode, MinecraftReadTypes, MinecraftWriteTypes};
+use crate::state::ConnectionState;
+use crate::text::TextComponent;
+use crate::types::Position;
+use crate::version::{PacketIds, ProtocolVersion};
+use super::{packet_enum, Packet};
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct DeathLocation {
+    pub dimension_name: String,
+    pub location: Position
+}
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct LoginPlay {
+    pub entity_id: i32,
+    pub is_hardcore: bool,
+    #[mc(length_prefixed)]
+    pub dimension_names: Vec<String>,
+    #[mc(varint)]
+    pub max_players: u32,
+    #[mc(varint)]
+    pub view_distance: u32,
+    #[mc(varint)]
+    pub simulation_distance: u32,
+    pub reduced_debug_info: bool,
+    pub enable_respawn_screen: bool,
+    pub do_limited_crafting: bool,
+    pub dimension_type: String,
+    pub dimension_name: String,
+    pub hashed_seed: i64,
+    pub game_mode: u8,
+    pub previous_game_mode: i8,
+    pub is_debug: bool,
+    pub is_flat: bool,
+    #[mc(optional)]
+    pub death_location: Option<DeathLocation>,
+    #[mc(varint)]
+    pub portal_cooldown: u32,
+    // since 1.20.5 the dimension type is sent as its registry id instead of dimension_type
+    #[mc(skip)]
+    pub dimension_type_id: u32,
+    // since 1.20.5
+    #[mc(skip)]
+    pub enforces_secure_chat: bool
+}
+
+impl Packet for LoginPlay {
+    const IDS: PacketIds = PacketIds::new(0x29, 0x29, 0x2B);
+
+    fn encode_for(&self, version: ProtocolVersion, buffer: &mut ByteBuffer) -> Result<()> {
+        if version < ProtocolVersion::V1_20_6 {
+            return self.encode(buffer);
+        }
+        self.entity_id.encode(buffer)?;
+        self.is_hardcore.encode(buffer)?;
+        buffer.write_var_int(self.dimension_names.len() as u32);
+        for dimension_name in &self.dimension_names {
+            dimension_name.encode(buffer)?;
+        }
+        buffer.write_var_int(self.max_players);
+        buffer.write_var_int(self.view_distance);
+        buffer.write_var_int(self.simulation_distance);
+        self.reduced_debug_info.encode(buffer)?;
+        self.enable_respawn_screen.encode(buffer)?;
+        self.do_limited_crafting.encode(buffer)?;
+        buffer.write_var_int(self.dimension_type_id);
+        self.dimension_name.encode(buffer)?;
+        self.hashed_seed.encode(buffer)?;
+        self.game_mode.encode(buffer)?;
+        self.previous_game_mode.encode(buffer)?;
+        self.is_debug.encode(buffer)?;
+        self.is_flat.encode(buffer)?;
+        self.death_location.is_some().encode(buffer)?;
+        if let Some(death_location) = &self.death_location {
+            death_location.encode(buffer)?;
+        }
+        buffer.write_var_int(self.portal_cooldown);
+        self.enforces_secure_chat.encode(buffer)
+    }
+
+    fn decode_for(version: ProtocolVersion, buffer: &mut ByteBuffer) -> Result<Self> {
+        if version < ProtocolVersion::V1_20_6 {
+            return Self::decode(buffer);
+        }
+        let entity_id = i32::decode(buffer)?;
+        let is_hardcore = bool::decode(buffer)?;
+        let dimension_names = buffer.read_length_prefixed()?;
+        Ok(LoginPlay {
+            entity_id,
+            is_hardcore,
+            dimension_names,
+            max_players: buffer.read_var_int()?,
+            view_distance: buffer.read_var_int()?,
+            simulation_distance: buffer.read_var_int()?,
+            reduced_debug_info: bool::decode(buffer)?,
+            enable_respawn_screen: bool::decode(buffer)?,
+            do_limited_crafting: bool::decode(buffer)?,
+            dimension_type_id: buffer.read_var_int()?,
+            dimension_type: String::new(),
+            dimension_name: String::decode(buffer)?,
+            hashed_seed: i64::decode(buffer)?,
+            game_mode: u8::decode(buffer)?,
+            previous_game_mode: i8::decode(buffer)?,
+            is_debug: bool::decode(buffer)?,
+            is_flat: bool::decode(buffer)?,
+            death_location: if bool::decode(buffer)? { Some(DeathLocation::decode(buffer)?) } else { None },
+            portal_cooldown: buffer.read_var_int()?,
+            enforces_secure_chat: bool::decode(buffer)?
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct SetCenterChunk {
+    #[mc(varint)]
+    pub chunk_x: i32,
+    #[mc(varint)]
+    pub chunk_z: i32
+}
+
+impl Packet for SetCenterChunk {
+    const IDS: PacketIds = PacketIds::new(0x50, 0x52, 0x54);
+}
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct GameEvent {
+    pub event: u8,
+    pub value: f32
+}
+
+impl Packet for GameEvent {
+    const IDS: PacketIds = PacketIds::new(0x20, 0x20, 0x22);
+}
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct BlockEntity {
+    pub packed_xz: u8,
+    pub y: i16,
+    #[mc(varint)]
+    pub kind: u32,
+    pub data: Value
+}
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct LightArray(#[mc(length_prefixed)] pub Vec<u8>);
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct ChunkDataAndUpdateLight {
+    pub chunk_x: i32,
+    pub chunk_z: i32,
+    pub heightmaps: Value,
+    #[mc(length_prefixed)]
+    pub data: Vec<u8>,
+    #[mc(length_prefixed)]
+    pub block_entities: Vec<BlockEntity>,
+    #[mc(length_prefixed)]
+    pub sky_light_mask: Vec<u64>,
+    #[mc(length_prefixed)]
+    pub block_light_mask: Vec<u64>,
+    #[mc(length_prefixed)]
+    pub empty_sky_light_mask: Vec<u64>,
+    #[mc(length_prefixed)]
+    pub empty_block_light_mask: Vec<u64>,
+    #[mc(length_prefixed)]
+    pub sky_light_arrays: Vec<LightArray>,
+    #[mc(length_prefixed)]
+    pub block_light_arrays: Vec<LightArray>
+}
+
+impl Packet for ChunkDataAndUpdateLight {
+    const IDS: PacketIds = PacketIds::new(0x25, 0x25, 0x27);
+}
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct SynchronizePlayerPosition {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub yaw: f32,
+    pub pitch: f32,
+    pub flags: u8,
+    #[mc(varint)]
+    pub teleport_id: u32
+}
+
+impl Packet for SynchronizePlayerPosition {
+    const IDS: PacketIds = PacketIds::new(0x3E, 0x3E, 0x40);
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockUpdate {
+    pub x: i32,
+    pub y: i16,
+    pub z: i32,
+    pub block_id: u32
+}
+
+impl McEncode for BlockUpdate {
+    fn encode(&self, buffer: &mut ByteBuffer) -> Result<()> {
+        buffer.write_position(&Position::new(self.x, self.y as i32, self.z));
+        buffer.write_var_int(self.block_id);
+        Ok(())
+    }
+}
+
+impl McDecode for BlockUpdate {
+    fn decode(buffer: &mut ByteBuffer) -> Result<Self> {
+        let location = buffer.read_position()?;
+        Ok(BlockUpdate {
+            x: location.x,
+            y: location.y as i16,
+            z: location.z,
+            block_id: buffer.read_var_int()?
+        })
+    }
+}
+
+impl Packet for BlockUpdate {
+    const IDS: PacketIds = PacketIds::same(0x09);
+}
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct SpawnEntity {
+    #[mc(varint)]
+    pub entity_id: u32,
+    pub entity_uuid: Uuid,
+    #[mc(varint)]
+    pub entity_type: u32,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub pitch: i8,
+    pub yaw: i8,
+    pub head_yaw: i8,
+    #[mc(varint)]
+    pub data: u32,
+    pub velocity_x: i16,
+    pub velocity_y: i16,
+    pub velocity_z: i16
+}
+
+impl Packet for SpawnEntity {
+    const IDS: PacketIds = PacketIds::same(0x01);
+}
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub enum MetadataValue {
+    #[mc(id = 0)]
+    Byte(i8),
+    #[mc(id = 1)]
+    VarInt(#[mc(varint)] i32),
+    #[mc(id = 3)]
+    Float(f32),
+    #[mc(id = 4)]
+    String(String),
+    #[mc(id = 8)]
+    Boolean(bool)
+}
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct EntityMetadata {
+    pub index: u8,
+    pub value: MetadataValue
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetEntityMetadata {
+    pub entity_id: u32,
+    pub metadata: Vec<EntityMetadata>
+}
+
+const METADATA_END: u8 = 0xff;
+
+impl McEncode for SetEntityMetadata {
+    fn encode(&self, buffer: &mut ByteBuffer) -> Result<()> {
+        buffer.write_var_int(self.entity_id);
+        for entry in &self.metadata {
+            entry.encode(buffer)?;
+        }
+        buffer.write_u8(METADATA_END);
+        Ok(())
+    }
+}
+
+impl McDecode for SetEntityMetadata {
+    fn decode(buffer: &mut ByteBuffer) -> Result<Self> {
+        let entity_id = buffer.read_var_int()?;
+        let mut metadata = Vec::new();
+        loop {
+            let index = buffer.read_u8()?;
+            if index == METADATA_END {
+                break;
+            }
+            metadata.push(EntityMetadata { index, value: MetadataValue::decode(buffer)? });
+        }
+        Ok(SetEntityMetadata { entity_id, metadata })
+    }
+}
+
+impl Packet for SetEntityMetadata {
+    const IDS: PacketIds = PacketIds::new(0x54, 0x56, 0x58);
+}
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct SetCamera {
+    #[mc(varint)]
+    pub camera_id: u32
+}
+
+impl Packet for SetCamera {
+    const IDS: PacketIds = PacketIds::new(0x4E, 0x50, 0x52);
+}
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct ClientboundKeepAlive {
+    pub keep_alive_id: u64
+}
+
+impl Packet for ClientboundKeepAlive {
+    const IDS: PacketIds = PacketIds::new(0x24, 0x24, 0x26);
+}
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct ServerboundKeepAlive {
+    pub keep_alive_id: u64
+}
+
+impl Packet for ServerboundKeepAlive {
+    const IDS: PacketIds = PacketIds::new(0x14, 0x15, 0x18);
+}
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct SetPlayerPosition {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub on_ground: bool
+}
+
+impl Packet for SetPlayerPosition {
+    const IDS: PacketIds = PacketIds::new(0x16, 0x17, 0x1A);
+}
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct SetPlayerPositionAndRotation {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub yaw: f32,
+    pub pitch: f32,
+    pub on_ground: bool
+}
+
+impl Packet for SetPlayerPositionAndRotation {
+    const IDS: PacketIds = PacketIds::new(0x17, 0x18, 0x1B);
+}
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct SetPlayerRotation {
+    pub yaw: f32,
+    pub pitch: f32,
+    pub on_ground: bool
+}
+
+impl Packet for SetPlayerRotation {
+    const IDS: PacketIds = PacketIds::new(0x18, 0x19, 0x1C);
+}
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct SetPlayerOnGround {
+    pub on_ground: bool
+}
+
+impl Packet for SetPlayerOnGround {
+    const IDS: PacketIds = PacketIds::new(0x19, 0x1A, 0x1D);
+}
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct ConfirmTeleportation {
+    #[mc(varint)]
+    pub teleport_id: u32
+}
+
+impl Packet for ConfirmTeleportation {
+    const IDS: PacketIds = PacketIds::same(0x00);
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayDisconnect {
+    pub reason: TextComponent
+}
+
+impl McEncode for PlayDisconnect {
+    fn encode(&self, buffer: &mut ByteBuffer) -> Result<()> {
+        self.reason.write_nbt(buffer)
+    }
+}
+
+impl McDecode for PlayDisconnect {
+    fn decode(buffer: &mut ByteBuffer) -> Result<Self> {
+        Ok(PlayDisconnect { reason: TextComponent::read_nbt(buffer)? })
+    }
+}
+
+impl Packet for PlayDisconnect {
+    const IDS: PacketIds = PacketIds::new(0x1B, 0x1B, 0x1D);
+
+    fn encode_for(&self, version: ProtocolVersion, buffer: &mut ByteBuffer) -> Result<()> {
+        self.reason.write_for(version, buffer)
+    }
+
+    fn decode_for(version: ProtocolVersion, buffer: &mut ByteBuffer) -> Result<Self> {
+        Ok(PlayDisconnect { reason: TextComponent::read_for(version, buffer)? })
+    }
+}
+
+packet_enum!(ServerboundPlayPacket, ConnectionState::Play, {
+    ConfirmTeleportation,
+    ServerboundKeepAlive,
+    SetPlayerPosition,
+    SetPlayerPositionAndRotation,
+    SetPlayerRotation,
+    SetPlayerOnGround
+});
+
+packet_enum!(ClientboundPlayPacket, ConnectionState::Play, {
+    PlayDisconnect,
+    SpawnEntity,
+    BlockUpdate,
+    GameEvent,
+    ClientboundKeepAlive,
+    ChunkDataAndUpdateLight,
+    LoginPlay,
+    SynchronizePlayerPosition,
+    SetCamera,
+    SetCenterChunk,
+    SetEntityMetadata
+});
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Deref;
+    use super::*;
+    use crate::error::ProtocolError;
+    use crate::packets::encode_packet;
+    use crate::packets::tests::{assert_round_trip, assert_round_trip_for};
+    use crate::world::{empty_chunk_sections, HEIGHT_MAP};
+
+    #[test]
+    fn login_play_round_trip() {
+        let mut packet = LoginPlay {
+            entity_id: 0,
+            is_hardcore: false,
+            dimension_names: vec!["minecraft:overworld".to_string()],
+            max_players: 100,
+            view_distance: 10,
+            simulation_distance: 10,
+            reduced_debug_info: false,
+            enable_respawn_screen: false,
+            do_limited_crafting: false,
+            dimension_type: "minecraft:overworld".to_string(),
+            dimension_name: "minecraft:overworld".to_string(),
+            hashed_seed: 0,
+            game_mode: 3,
+            previous_game_mode: -1,
+            is_debug: false,
+            is_flat: false,
+            death_location: None,
+            portal_cooldown: 0,
+            dimension_type_id: 0,
+            enforces_secure_chat: false
+        };
+        assert_round_trip(packet.clone());
+        packet.death_location = Some(DeathLocation { dimension_name: "minecraft:overworld".to_string(), location: Position::new(1, 64, -1) });
+        assert_round_trip(packet.clone());
+        assert_round_trip_for(ProtocolVersion::V1_20_2, packet.clone());
+
+        // 1.20.5 drops the dimension type name for its id
+        packet.dimension_type = String::new();
+        packet.dimension_type_id = 3;
+        packet.enforces_secure_chat = true;
+        assert_round_trip_for(ProtocolVersion::V1_20_6, packet);
+
+        // entity id, hardcore, then a dimension count far past the end of the packet
+        let mut buffer = ByteBuffer::from_bytes(&[0, 0, 0, 0, 0, 0xff, 0xff, 0xff, 0xff, 0x07]);
+        assert!(matches!(LoginPlay::decode_for(ProtocolVersion::V1_20_6, &mut buffer), Err(ProtocolError::ArrayTooLong { .. })));
+    }
+
+    #[test]
+    fn chunk_data_round_trip() {
+        assert_round_trip(ChunkDataAndUpdateLight {
+            chunk_x: -3,
+            chunk_z: 7,
+            heightmaps: HEIGHT_MAP.deref().clone(),
+            data: empty_chunk_sections(24),
+            block_entities: vec![],
+            sky_light_mask: vec![0b101],
+            block_light_mask: vec![],
+            empty_sky_light_mask: vec![],
+            empty_block_light_mask: vec![u64::MAX],
+            sky_light_arrays: vec![LightArray(vec![0xff; 2048]), LightArray(vec![0; 2048])],
+            block_light_arrays: vec![]
+        });
+    }
+
+    #[test]
+    fn play_packets_round_trip() {
+        assert_round_trip(SetCenterChunk { chunk_x: -1, chunk_z: 12 });
+        assert_round_trip(GameEvent { event: 13, value: 0.0 });
+        assert_round_trip(SynchronizePlayerPosition { x: 0.5, y: 1.0, z: 0.5, yaw: 90.0, pitch: -10.0, flags: 0, teleport_id: 3 });
+        assert_round_trip(BlockUpdate { x: -4, y: -64, z: 30_000_000, block_id: 7406 });
+        assert_round_trip(SpawnEntity {
+            entity_id: 1,
+            entity_uuid: Uuid::new_v4(),
+            entity_type: 2,
+            x: 0.5,
+            y: 1.0,
+            z: 0.5,
+            pitch: -64,
+            yaw: 0,
+            head_yaw: 0,
+            data: 0,
+            velocity_x: -1,
+            velocity_y: 0,
+            velocity_z: 1
+        });
+        assert_round_trip(SetEntityMetadata {
+            entity_id: 1,
+            metadata: vec![
+                EntityMetadata { index: 0, value: MetadataValue::Byte(0x20) },
+                EntityMetadata { index: 1, value: MetadataValue::VarInt(-5) },
+                EntityMetadata { index: 2, value: MetadataValue::String("name".to_string()) },
+                EntityMetadata { index: 3, value: MetadataValue::Boolean(true) },
+                EntityMetadata { index: 4, value: MetadataValue::Float(0.25) }
+            ]
+        });
+        assert_round_trip(SetCamera { camera_id: 1 });
+        assert_round_trip(ClientboundKeepAlive { keep_alive_id: 42 });
+        assert_round_trip(ServerboundKeepAlive { keep_alive_id: 42 });
+        assert_round_trip(SetPlayerPosition { x: 1.5, y: -60.0, z: 3.25, on_ground: true });
+        assert_round_trip(SetPlayerPositionAndRotation { x: 1.5, y: -60.0, z: 3.25, yaw: 45.0, pitch: 90.0, on_ground: false });
+        assert_round_trip(SetPlayerRotation { yaw: -45.0, pitch: 0.0, on_ground: true });
+        assert_round_trip(SetPlayerOnGround { on_ground: false });
+        assert_round_trip(ConfirmTeleportation { teleport_id: 0 });
+    }
+
+    #[test]
+    fn disconnect_reason_per_version() {
+        let disconnect = PlayDisconnect { reason: TextComponent::text("Kicked").bold(true) };
+        for version in ProtocolVersion::SUPPORTED {
+            assert_round_trip_for(version, disconnect.clone());
+        }
+        // json string before 1.20.3, nbt compound after
+        let json = encode_packet(ProtocolVersion::V1_20_2, &disconnect).unwrap();
+        assert_eq!(json.as_bytes()[1] as usize, r#"{"text":"Kicked","bold":true}"#.len());
+        let nbt = encode_packet(ProtocolVersion::V1_20_4, &disconnect).unwrap();
+        assert_eq!(nbt.as_bytes()[1], 0x0a);
+    }
+
+    #[test]
+    fn serverbound_ids_per_version() {
+        let mut buffer = ByteBuffer::from_bytes(&7u64.to_be_bytes());
+        assert_eq!(
+            ServerboundPlayPacket::decode(ProtocolVersion::V1_20_2, 0x14, &mut buffer).unwrap(),
+            ServerboundPlayPacket::ServerboundKeepAlive(ServerboundKeepAlive { keep_alive_id: 7 })
+        );
+        assert!(ServerboundPlayPacket::contains(ProtocolVersion::V1_20_6, 0x1D));
+        assert!(!ServerboundPlayPacket::contains(ProtocolVersion::V1_20_6, 0x17));
+        assert_eq!(ClientboundPlayPacket::SetCenterChunk(SetCenterChunk { chunk_x: 0, chunk_z: 0 }).id(ProtocolVersion::V1_20_6), Some(0x54));
+    }
+}
@@ -0,0 +1,62 @@
+use crate::codec::{McDecode, McEncode};
+use crate::state::ConnectionState;
+use crate::version::PacketIds;
+use super::{packet_enum, Packet};
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct StatusRequest;
+
+impl Packet for StatusRequest {
+    const IDS: PacketIds = PacketIds::same(0x00);
+}
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct PingRequest {
+    pub payload: u64
+}
+
+impl Packet for PingRequest {
+    const IDS: PacketIds = PacketIds::same(0x01);
+}
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct StatusResponse {
+    pub json_response: String
+}
+
+impl Packet for StatusResponse {
+    const IDS: PacketIds = PacketIds::same(0x00);
+}
+
+#[derive(Debug, Clone, PartialEq, McEncode, McDecode)]
+pub struct PingResponse {
+    pub payload: u64
+}
+
+impl Packet for PingResponse {
+    const IDS: PacketIds = PacketIds::same(0x01);
+}
+
+packet_enum!(ServerboundStatusPacket, ConnectionState::Status, {
+    StatusRequest,
+    PingRequest
+});
+
+packet_enum!(ClientboundStatusPacket, ConnectionState::Status, {
+    StatusResponse,
+    PingResponse
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packets::tests::assert_round_trip;
+
+    #[test]
+    fn status_packets_round_trip() {
+        assert_round_trip(StatusRequest);
+        assert_round_trip(PingRequest { payload: u64::MAX });
+        assert_round_trip(StatusResponse { json_response: "{\"text\":\"hi\"}".to_string() });
+        assert_round_trip(PingResponse { payload: 1234567 });
+    }
+}
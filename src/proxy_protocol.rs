@@ -0,0 +1,198 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use crate::error::{ProtocolError, Result};
+
+const V1_SIGNATURE: &[u8] = b"PROXY ";
+// the longest possible v1 header, TCP6 with both addresses and ports at their longest
+const V1_MAX_LENGTH: usize = 107;
+const V2_SIGNATURE: [u8; 12] = *b"\r\n\r\n\0\r\nQUIT\n";
+const V2_HEADER_LENGTH: usize = 16;
+// enough to tell the two versions apart without reading past the shortest v1 header
+const SIGNATURE_PREFIX_LENGTH: usize = 8;
+
+// what a load balancer speaking the PROXY protocol says about the connection it accepted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProxyHeader {
+    // None for the balancer's own health checks and address families other than TCP/UDP over IP,
+    // the socket's peer address stays in use then
+    pub source: Option<SocketAddr>,
+    pub destination: Option<SocketAddr>
+}
+
+impl ProxyHeader {
+    const LOCAL: ProxyHeader = ProxyHeader { source: None, destination: None };
+
+    // parses a complete v1 or v2 header
+    pub fn parse(bytes: &[u8]) -> Result<ProxyHeader> {
+        if bytes.starts_with(&V2_SIGNATURE) {
+            parse_v2(&bytes[V2_SIGNATURE.len()..])
+        } else if bytes.starts_with(V1_SIGNATURE) {
+            parse_v1(&bytes[V1_SIGNATURE.len()..])
+        } else {
+            Err(invalid("missing signature"))
+        }
+    }
+}
+
+// reads exactly the header, so the minecraft data after it stays in the reader
+pub async fn read_proxy_header<R: AsyncRead + Unpin>(reader: &mut R) -> Result<ProxyHeader> {
+    let mut header = vec![0; SIGNATURE_PREFIX_LENGTH];
+    reader.read_exact(&mut header).await?;
+    if header.starts_with(V1_SIGNATURE) {
+        // v1 has no length, the header ends at the first CRLF
+        while !header.ends_with(b"\r\n") {
+            if header.len() == V1_MAX_LENGTH {
+                return Err(invalid(&format!("v1 header is longer than {V1_MAX_LENGTH} bytes")));
+            }
+            header.push(reader.read_u8().await?);
+        }
+    } else if V2_SIGNATURE.starts_with(&header) {
+        header.resize(V2_HEADER_LENGTH, 0);
+        reader.read_exact(&mut header[SIGNATURE_PREFIX_LENGTH..]).await?;
+        let length = u16::from_be_bytes([header[14], header[15]]) as usize;
+        header.resize(V2_HEADER_LENGTH + length, 0);
+        reader.read_exact(&mut header[V2_HEADER_LENGTH..]).await?;
+    }
+    ProxyHeader::parse(&header)
+}
+
+// `TCP4 source destination source_port destination_port\r\n` or `UNKNOWN ...\r\n`
+fn parse_v1(bytes: &[u8]) -> Result<ProxyHeader> {
+    let line = bytes.strip_suffix(b"\r\n").ok_or_else(|| invalid("v1 header doesn't end with CRLF"))?;
+    let line = std::str::from_utf8(line).map_err(|_| invalid("v1 header isn't ascii"))?;
+    let mut fields = line.split(' ');
+    let is_v4 = match fields.next() {
+        Some("TCP4") => true,
+        Some("TCP6") => false,
+        // the rest of the line is undefined
+        Some("UNKNOWN") => return Ok(ProxyHeader::LOCAL),
+        _ => return Err(invalid("unknown v1 protocol"))
+    };
+    let (Some(source), Some(destination), Some(source_port), Some(destination_port), None) =
+        (fields.next(), fields.next(), fields.next(), fields.next(), fields.next()) else {
+        return Err(invalid("v1 header needs two addresses and two ports"));
+    };
+    let address = |address: &str| -> Result<IpAddr> {
+        let address = match is_v4 {
+            true => address.parse::<Ipv4Addr>().map(IpAddr::from),
+            false => address.parse::<Ipv6Addr>().map(IpAddr::from)
+        };
+        address.map_err(|_| invalid("invalid v1 address"))
+    };
+    let port = |port: &str| -> Result<u16> {
+        // ports are written without leading zeros
+        if port.len() > 1 && port.starts_with('0') {
+            return Err(invalid("invalid v1 port"));
+        }
+        port.parse().map_err(|_| invalid("invalid v1 port"))
+    };
+    Ok(ProxyHeader {
+        source: Some(SocketAddr::new(address(source)?, port(source_port)?)),
+        destination: Some(SocketAddr::new(address(destination)?, port(destination_port)?))
+    })
+}
+
+// version and command, address family and protocol, length, then the addresses and optional TLVs
+fn parse_v2(bytes: &[u8]) -> Result<ProxyHeader> {
+    let [version_command, family, length_high, length_low, rest @ ..] = bytes else {
+        return Err(invalid("v2 header is too short"));
+    };
+    if version_command >> 4 != 2 {
+        return Err(invalid(&format!("unknown version {}", version_command >> 4)));
+    }
+    let length = u16::from_be_bytes([*length_high, *length_low]) as usize;
+    if rest.len() != length {
+        return Err(invalid(&format!("v2 header announces {length} bytes of addresses, got {}", rest.len())));
+    }
+    match version_command & 0x0f {
+        // sent by the balancer itself, not on behalf of a client
+        0x0 => return Ok(ProxyHeader::LOCAL),
+        0x1 => {}
+        command => return Err(invalid(&format!("unknown v2 command {command}")))
+    }
+    // the low nibble is the transport, TCP and UDP look the same here
+    let (source, destination) = match family >> 4 {
+        0x1 => {
+            let addresses = rest.get(..12).ok_or_else(|| invalid("v2 IPv4 addresses are too short"))?;
+            let source = <[u8; 4]>::try_from(&addresses[..4]).unwrap();
+            let destination = <[u8; 4]>::try_from(&addresses[4..8]).unwrap();
+            (IpAddr::from(source), IpAddr::from(destination))
+        }
+        0x2 => {
+            let addresses = rest.get(..36).ok_or_else(|| invalid("v2 IPv6 addresses are too short"))?;
+            let source = <[u8; 16]>::try_from(&addresses[..16]).unwrap();
+            let destination = <[u8; 16]>::try_from(&addresses[16..32]).unwrap();
+            (IpAddr::from(source), IpAddr::from(destination))
+        }
+        // unspecified and unix sockets don't carry an address we could use
+        _ => return Ok(ProxyHeader::LOCAL)
+    };
+    let ports = match source {
+        IpAddr::V4(_) => &rest[8..12],
+        IpAddr::V6(_) => &rest[32..36]
+    };
+    Ok(ProxyHeader {
+        source: Some(SocketAddr::new(source, u16::from_be_bytes([ports[0], ports[1]]))),
+        destination: Some(SocketAddr::new(destination, u16::from_be_bytes([ports[2], ports[3]])))
+    })
+}
+
+fn invalid(reason: &str) -> ProtocolError {
+    ProtocolError::InvalidProxyHeader(reason.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v2(command: u8, family: u8, addresses: &[u8]) -> Vec<u8> {
+        let mut header = V2_SIGNATURE.to_vec();
+        header.extend_from_slice(&[0x20 | command, family]);
+        header.extend_from_slice(&(addresses.len() as u16).to_be_bytes());
+        header.extend_from_slice(addresses);
+        header
+    }
+
+    #[test]
+    fn parses_both_versions() {
+        let header = ProxyHeader::parse(b"PROXY TCP4 203.0.113.7 192.0.2.1 51234 25565\r\n").unwrap();
+        assert_eq!(header.source, Some("203.0.113.7:51234".parse().unwrap()));
+        assert_eq!(header.destination, Some("192.0.2.1:25565".parse().unwrap()));
+        let header = ProxyHeader::parse(b"PROXY TCP6 2001:db8::1 2001:db8::2 1 25565\r\n").unwrap();
+        assert_eq!(header.source, Some("[2001:db8::1]:1".parse().unwrap()));
+        assert_eq!(ProxyHeader::parse(b"PROXY UNKNOWN whatever\r\n").unwrap(), ProxyHeader::LOCAL);
+        assert!(ProxyHeader::parse(b"PROXY TCP4 2001:db8::1 192.0.2.1 1 2\r\n").is_err());
+        assert!(ProxyHeader::parse(b"PROXY TCP4 203.0.113.7 192.0.2.1 01 2\r\n").is_err());
+        assert!(ProxyHeader::parse(b"PROXY TCP4 203.0.113.7 192.0.2.1 1\r\n").is_err());
+
+        // TCP over IPv4 with a trailing TLV that is skipped
+        let header = ProxyHeader::parse(&v2(1, 0x11, &[203, 0, 113, 7, 192, 0, 2, 1, 0xc8, 0x22, 0x63, 0xdd, 0x04, 0, 1, 0])).unwrap();
+        assert_eq!(header.source, Some("203.0.113.7:51234".parse().unwrap()));
+        assert_eq!(header.destination, Some("192.0.2.1:25565".parse().unwrap()));
+        let mut addresses = [0; 36];
+        addresses[15] = 1;
+        addresses[31] = 2;
+        addresses[32..].copy_from_slice(&[0, 1, 0x63, 0xdd]);
+        assert_eq!(ProxyHeader::parse(&v2(1, 0x21, &addresses)).unwrap().source, Some("[::1]:1".parse().unwrap()));
+        assert_eq!(ProxyHeader::parse(&v2(0, 0x00, &[])).unwrap(), ProxyHeader::LOCAL);
+        assert!(ProxyHeader::parse(&v2(1, 0x11, &[203, 0, 113, 7])).is_err());
+        assert!(ProxyHeader::parse(&v2(2, 0x11, &[0; 12])).is_err());
+        let mut version_1 = v2(1, 0x11, &[0; 12]);
+        version_1[12] = 0x11;
+        assert!(ProxyHeader::parse(&version_1).is_err());
+    }
+
+    #[tokio::test]
+    async fn leaves_the_rest_of_the_stream() {
+        for header in [b"PROXY UNKNOWN\r\n".to_vec(), v2(1, 0x11, &[127, 0, 0, 1, 127, 0, 0, 1, 0, 1, 0, 2])] {
+            let stream = [header.as_slice(), &[0x10, 0x00]].concat();
+            let mut reader = stream.as_slice();
+            read_proxy_header(&mut reader).await.unwrap();
+            assert_eq!(reader, [0x10, 0x00]);
+        }
+        let mut minecraft: &[u8] = &[0x10, 0x00, 0xfd, 0x05, 0x09, 0x6c, 0x6f, 0x63, 0x61];
+        assert!(read_proxy_header(&mut minecraft).await.is_err());
+    }
+}
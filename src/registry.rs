@@ -0,0 +1,834 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use fastnbt::Value;
+use once_cell::sync::Lazy;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ProtocolError, Result};
+use crate::packets::configuration::RegistryEntries;
+use crate::text::{Style, TextComponent};
+use crate::types::Identifier;
+use crate::version::ProtocolVersion;
+
+pub const BIOME_REGISTRY: &str = "minecraft:worldgen/biome";
+pub const CHAT_TYPE_REGISTRY: &str = "minecraft:chat_type";
+pub const DAMAGE_TYPE_REGISTRY: &str = "minecraft:damage_type";
+pub const DIMENSION_TYPE_REGISTRY: &str = "minecraft:dimension_type";
+pub const TRIM_MATERIAL_REGISTRY: &str = "minecraft:trim_material";
+pub const TRIM_PATTERN_REGISTRY: &str = "minecraft:trim_pattern";
+pub const WOLF_VARIANT_REGISTRY: &str = "minecraft:wolf_variant";
+
+// what the demo server sends, datapacks can add to it or replace entries
+pub static REGISTRY: Lazy<Registries> = Lazy::new(default_registries);
+
+// entries in the order they were added, their index is the id other packets refer to them by
+#[derive(Debug, Clone, PartialEq)]
+pub struct Registry<T> {
+    entries: Vec<(Identifier, T)>
+}
+
+// the registries sent in Registry Data
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Registries {
+    pub biomes: Registry<Biome>,
+    pub chat_types: Registry<ChatType>,
+    pub damage_types: Registry<DamageType>,
+    pub dimension_types: Registry<DimensionType>,
+    pub trim_materials: Registry<TrimMaterial>,
+    pub trim_patterns: Registry<TrimPattern>,
+    // only known to 1.20.5+ clients
+    pub wolf_variants: Registry<WolfVariant>
+}
+
+// entries use the field names of the vanilla json files, unknown fields like a biome's features are ignored
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Biome {
+    pub has_precipitation: bool,
+    pub temperature: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature_modifier: Option<String>,
+    pub downfall: f32,
+    pub effects: BiomeEffects
+}
+
+// particles aren't kept, their options differ for every particle type
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BiomeEffects {
+    pub fog_color: i32,
+    pub water_color: i32,
+    pub water_fog_color: i32,
+    pub sky_color: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub foliage_color: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grass_color: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grass_color_modifier: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ambient_sound: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mood_sound: Option<MoodSound>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub additions_sound: Option<AdditionsSound>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub music: Option<Music>
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MoodSound {
+    pub sound: String,
+    pub tick_delay: i32,
+    pub block_search_extent: i32,
+    pub offset: f64
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AdditionsSound {
+    pub sound: String,
+    pub tick_chance: f64
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Music {
+    pub sound: String,
+    pub min_delay: i32,
+    pub max_delay: i32,
+    pub replace_current_music: bool
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatType {
+    pub chat: ChatDecoration,
+    pub narration: ChatDecoration
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatDecoration {
+    pub translation_key: String,
+    // any of `sender`, `target` and `content`, in the order the translation uses them
+    pub parameters: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub style: Option<Style>
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DamageType {
+    // death messages are `death.attack.<message_id>`
+    pub message_id: String,
+    pub scaling: DamageScaling,
+    pub exhaustion: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub effects: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub death_message_type: Option<String>
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DamageScaling {
+    Never,
+    WhenCausedByLivingNonPlayer,
+    Always
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DimensionType {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixed_time: Option<i64>,
+    pub has_skylight: bool,
+    pub has_ceiling: bool,
+    pub ultrawarm: bool,
+    pub natural: bool,
+    pub coordinate_scale: f64,
+    pub bed_works: bool,
+    pub respawn_anchor_works: bool,
+    pub min_y: i32,
+    pub height: i32,
+    pub logical_height: i32,
+    pub infiniburn: String,
+    pub effects: String,
+    pub ambient_light: f32,
+    pub piglin_safe: bool,
+    pub has_raids: bool,
+    pub monster_spawn_light_level: IntProvider,
+    pub monster_spawn_block_light_limit: i32
+}
+
+// a constant or `{type, value: {min_inclusive, max_inclusive}}`, flattened to `{type, min_inclusive, max_inclusive}`
+// by to_codec for 1.20.5 clients
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged, from = "IntProviderRepr")]
+pub enum IntProvider {
+    Constant(i32),
+    Ranged {
+        #[serde(rename = "type")]
+        kind: String,
+        value: IntRange
+    }
+}
+
+// 1.20.5 datapacks leave out the `value` level
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum IntProviderRepr {
+    Constant(i32),
+    Ranged {
+        #[serde(rename = "type")]
+        kind: String,
+        value: IntRange
+    },
+    Flattened {
+        #[serde(rename = "type")]
+        kind: String,
+        #[serde(flatten)]
+        value: IntRange
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntRange {
+    pub min_inclusive: i32,
+    pub max_inclusive: i32
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrimMaterial {
+    pub asset_name: String,
+    pub ingredient: String,
+    pub item_model_index: f32,
+    // armor material to the asset name used on it instead
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub override_armor_materials: BTreeMap<String, String>,
+    pub description: TextComponent
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrimPattern {
+    pub asset_id: String,
+    pub template_item: String,
+    pub description: TextComponent,
+    #[serde(default)]
+    pub decal: bool
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WolfVariant {
+    pub wild_texture: String,
+    pub tame_texture: String,
+    pub angry_texture: String,
+    // a biome, a `#tag` or a list of biomes
+    pub biomes: Biomes
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Biomes {
+    One(String),
+    List(Vec<String>)
+}
+
+// adds entries one by one or from datapacks, later entries replace earlier ones with the same id
+#[derive(Debug, Clone, Default)]
+pub struct RegistryBuilder {
+    registries: Registries
+}
+
+impl From<IntProviderRepr> for IntProvider {
+    fn from(repr: IntProviderRepr) -> Self {
+        match repr {
+            IntProviderRepr::Constant(value) => IntProvider::Constant(value),
+            IntProviderRepr::Ranged { kind, value } | IntProviderRepr::Flattened { kind, value } => IntProvider::Ranged { kind, value }
+        }
+    }
+}
+
+impl<T> Default for Registry<T> {
+    fn default() -> Self {
+        Registry { entries: vec![] }
+    }
+}
+
+impl<T> Registry<T> {
+    pub fn new() -> Self {
+        Registry::default()
+    }
+
+    // a replaced entry keeps its id, so the ids of the others don't change
+    pub fn insert(&mut self, id: Identifier, entry: T) {
+        match self.entries.iter_mut().find(|(existing, _)| *existing == id) {
+            Some((_, existing)) => *existing = entry,
+            None => self.entries.push((id, entry))
+        }
+    }
+
+    pub fn get(&self, id: &Identifier) -> Option<&T> {
+        self.entries.iter().find(|(existing, _)| existing == id).map(|(_, entry)| entry)
+    }
+
+    pub fn id_of(&self, id: &Identifier) -> Option<u32> {
+        self.entries.iter().position(|(existing, _)| existing == id).map(|index| index as u32)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Identifier, &T)> {
+        self.entries.iter().map(|(id, entry)| (id, entry))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl<T: Serialize> Registry<T> {
+    // `{type, value: [{name, id, element}]}` as in the registry codec
+    fn to_nbt(&self, registry_id: &str) -> Result<Value> {
+        let mut values = Vec::with_capacity(self.entries.len());
+        for (id, (name, entry)) in self.entries.iter().enumerate() {
+            values.push(Value::Compound(HashMap::from([
+                ("name".to_string(), Value::String(name.to_string())),
+                ("id".to_string(), Value::Int(id as i32)),
+                ("element".to_string(), fastnbt::to_value(entry)?)
+            ])));
+        }
+        Ok(Value::Compound(HashMap::from([
+            ("type".to_string(), Value::String(registry_id.to_string())),
+            ("value".to_string(), Value::List(values))
+        ])))
+    }
+}
+
+impl Registries {
+    // the registry codec of Registry Data up to 1.20.4,
+    // empty registries and ones the version doesn't know are left out
+    pub fn to_codec(&self, version: ProtocolVersion) -> Result<Value> {
+        let mut codec = HashMap::new();
+        add_registry(&mut codec, BIOME_REGISTRY, &self.biomes)?;
+        add_registry(&mut codec, CHAT_TYPE_REGISTRY, &self.chat_types)?;
+        add_registry(&mut codec, DAMAGE_TYPE_REGISTRY, &self.damage_types)?;
+        add_registry(&mut codec, DIMENSION_TYPE_REGISTRY, &self.dimension_types)?;
+        add_registry(&mut codec, TRIM_MATERIAL_REGISTRY, &self.trim_materials)?;
+        add_registry(&mut codec, TRIM_PATTERN_REGISTRY, &self.trim_patterns)?;
+        if version >= ProtocolVersion::V1_20_6 {
+            add_registry(&mut codec, WOLF_VARIANT_REGISTRY, &self.wolf_variants)?;
+            if let Some(dimension_types) = codec.get_mut(DIMENSION_TYPE_REGISTRY) {
+                flatten_int_providers(dimension_types, "monster_spawn_light_level");
+            }
+        }
+        Ok(Value::Compound(codec))
+    }
+
+    // one Registry Data packet per registry, since 1.20.5
+    pub fn to_entries(&self, version: ProtocolVersion) -> Result<Vec<RegistryEntries>> {
+        Ok(RegistryEntries::from_codec(&self.to_codec(version)?))
+    }
+}
+
+// moves the range of the ranged int provider `key` of every element up next to its type, as 1.20.5 expects
+fn flatten_int_providers(registry: &mut Value, key: &str) {
+    let Some(Value::List(entries)) = (match registry {
+        Value::Compound(registry) => registry.get_mut("value"),
+        _ => None
+    }) else {
+        return;
+    };
+    for entry in entries {
+        let Value::Compound(entry) = entry else { continue };
+        let Some(Value::Compound(element)) = entry.get_mut("element") else { continue };
+        let Some(Value::Compound(provider)) = element.get_mut(key) else { continue };
+        if let Some(Value::Compound(range)) = provider.remove("value") {
+            provider.extend(range);
+        }
+    }
+}
+
+fn add_registry<T: Serialize>(codec: &mut HashMap<String, Value>, registry_id: &str, registry: &Registry<T>) -> Result<()> {
+    if !registry.is_empty() {
+        codec.insert(registry_id.to_string(), registry.to_nbt(registry_id)?);
+    }
+    Ok(())
+}
+
+impl From<Registries> for RegistryBuilder {
+    fn from(registries: Registries) -> Self {
+        RegistryBuilder { registries }
+    }
+}
+
+impl RegistryBuilder {
+    pub fn new() -> Self {
+        RegistryBuilder::default()
+    }
+
+    pub fn biome(mut self, id: Identifier, biome: Biome) -> Self {
+        self.registries.biomes.insert(id, biome);
+        self
+    }
+
+    pub fn chat_type(mut self, id: Identifier, chat_type: ChatType) -> Self {
+        self.registries.chat_types.insert(id, chat_type);
+        self
+    }
+
+    pub fn damage_type(mut self, id: Identifier, damage_type: DamageType) -> Self {
+        self.registries.damage_types.insert(id, damage_type);
+        self
+    }
+
+    pub fn dimension_type(mut self, id: Identifier, dimension_type: DimensionType) -> Self {
+        self.registries.dimension_types.insert(id, dimension_type);
+        self
+    }
+
+    pub fn trim_material(mut self, id: Identifier, trim_material: TrimMaterial) -> Self {
+        self.registries.trim_materials.insert(id, trim_material);
+        self
+    }
+
+    pub fn trim_pattern(mut self, id: Identifier, trim_pattern: TrimPattern) -> Self {
+        self.registries.trim_patterns.insert(id, trim_pattern);
+        self
+    }
+
+    pub fn wolf_variant(mut self, id: Identifier, wolf_variant: WolfVariant) -> Self {
+        self.registries.wolf_variants.insert(id, wolf_variant);
+        self
+    }
+
+    // reads `data/<namespace>/<registry path>/<name>.json` like vanilla,
+    // so `data/minecraft/worldgen/biome/plains.json` is the biome `minecraft:plains`
+    pub fn datapack(mut self, path: impl AsRef<Path>) -> Result<Self> {
+        let data = path.as_ref().join("data");
+        for namespace in sorted_entries(&data)? {
+            if !namespace.is_dir() {
+                continue;
+            }
+            let name = namespace.file_name().unwrap_or_default().to_string_lossy().to_string();
+            let registries = &mut self.registries;
+            load_registry(&namespace, &name, BIOME_REGISTRY, &mut registries.biomes)?;
+            load_registry(&namespace, &name, CHAT_TYPE_REGISTRY, &mut registries.chat_types)?;
+            load_registry(&namespace, &name, DAMAGE_TYPE_REGISTRY, &mut registries.damage_types)?;
+            load_registry(&namespace, &name, DIMENSION_TYPE_REGISTRY, &mut registries.dimension_types)?;
+            load_registry(&namespace, &name, TRIM_MATERIAL_REGISTRY, &mut registries.trim_materials)?;
+            load_registry(&namespace, &name, TRIM_PATTERN_REGISTRY, &mut registries.trim_patterns)?;
+            load_registry(&namespace, &name, WOLF_VARIANT_REGISTRY, &mut registries.wolf_variants)?;
+        }
+        Ok(self)
+    }
+
+    pub fn build(self) -> Registries {
+        self.registries
+    }
+}
+
+// sorted so the ids don't depend on the file system
+fn sorted_entries(directory: &Path) -> Result<Vec<PathBuf>> {
+    let mut entries = std::fs::read_dir(directory)
+        .map_err(|e| invalid_datapack(directory, e))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    entries.sort();
+    Ok(entries)
+}
+
+fn load_registry<T: DeserializeOwned>(namespace: &Path, name: &str, registry_id: &str, registry: &mut Registry<T>) -> Result<()> {
+    let registry_path = registry_id.trim_start_matches("minecraft:");
+    let directory = namespace.join(registry_path);
+    if directory.is_dir() {
+        load_entries(&directory, name, "", registry)?;
+    }
+    Ok(())
+}
+
+// entries in subdirectories get the directories in their path, like `minecraft:custom/fire`
+fn load_entries<T: DeserializeOwned>(directory: &Path, namespace: &str, prefix: &str, registry: &mut Registry<T>) -> Result<()> {
+    for path in sorted_entries(directory)? {
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        if path.is_dir() {
+            load_entries(&path, namespace, &format!("{prefix}{file_name}/"), registry)?;
+            continue;
+        }
+        let Some(name) = file_name.strip_suffix(".json") else {
+            continue;
+        };
+        let id = Identifier::new(namespace, &format!("{prefix}{name}"))?;
+        let json = std::fs::read_to_string(&path).map_err(|e| invalid_datapack(&path, e))?;
+        let entry = serde_json::from_str(&json).map_err(|e| invalid_datapack(&path, e))?;
+        registry.insert(id, entry);
+    }
+    Ok(())
+}
+
+fn invalid_datapack(path: &Path, error: impl std::fmt::Display) -> ProtocolError {
+    ProtocolError::InvalidDatapack(format!("{}: {error}", path.display()))
+}
+
+fn minecraft(path: &str) -> Identifier {
+    Identifier::minecraft(path).expect("vanilla ids are valid")
+}
+
+// vanilla's sky color for a temperature, from OverworldBiomes.calculateSkyColor
+fn sky_color(temperature: f32) -> i32 {
+    let warmth = (temperature / 3.0).clamp(-1.0, 1.0);
+    let (hue, saturation) = (0.622_222_24 - warmth * 0.05, 0.5 + warmth * 0.1);
+    let sector = (hue * 6.0) as i32 % 6;
+    let fraction = hue * 6.0 - sector as f32;
+    let (p, q, t) = (1.0 - saturation, 1.0 - fraction * saturation, 1.0 - (1.0 - fraction) * saturation);
+    let (red, green, blue) = match sector {
+        0 => (1.0, t, p),
+        1 => (q, 1.0, p),
+        2 => (p, 1.0, t),
+        3 => (p, q, 1.0),
+        4 => (t, p, 1.0),
+        _ => (1.0, p, q)
+    };
+    let channel = |value: f32| ((value * 255.0) as i32).clamp(0, 255);
+    channel(red) << 16 | channel(green) << 8 | channel(blue)
+}
+
+fn mood_sound(sound: &str) -> MoodSound {
+    MoodSound { sound: sound.to_string(), tick_delay: 6000, block_search_extent: 8, offset: 2.0 }
+}
+
+fn overworld_biome(has_precipitation: bool, temperature: f32, downfall: f32) -> Biome {
+    Biome {
+        has_precipitation,
+        temperature,
+        temperature_modifier: None,
+        downfall,
+        effects: BiomeEffects {
+            fog_color: 0xc0d8ff,
+            water_color: 0x3f76e4,
+            water_fog_color: 0x050533,
+            sky_color: sky_color(temperature),
+            foliage_color: None,
+            grass_color: None,
+            grass_color_modifier: None,
+            ambient_sound: None,
+            mood_sound: Some(mood_sound("minecraft:ambient.cave")),
+            additions_sound: None,
+            music: None
+        }
+    }
+}
+
+// the nether biomes only differ in their fog and ambient sounds
+fn nether_biome(name: &str, fog_color: i32) -> Biome {
+    let mut biome = overworld_biome(false, 2.0, 0.0);
+    biome.effects.fog_color = fog_color;
+    biome.effects.ambient_sound = Some(format!("minecraft:ambient.{name}.loop"));
+    biome.effects.mood_sound = Some(mood_sound(&format!("minecraft:ambient.{name}.mood")));
+    biome.effects.additions_sound = Some(AdditionsSound { sound: format!("minecraft:ambient.{name}.additions"), tick_chance: 0.0111 });
+    biome
+}
+
+fn end_biome() -> Biome {
+    let mut biome = overworld_biome(false, 0.5, 0.5);
+    biome.effects.fog_color = 0xa080a0;
+    biome.effects.sky_color = 0;
+    biome
+}
+
+// every vanilla biome with its climate and colors, music is left to the client.
+// Plains comes first because the demo world's chunks use biome id 0
+fn vanilla_biomes() -> Vec<(&'static str, Biome)> {
+    let overworld = [
+        ("plains", true, 0.8, 0.4),
+        ("badlands", false, 2.0, 0.0),
+        ("bamboo_jungle", true, 0.95, 0.9),
+        ("beach", true, 0.8, 0.4),
+        ("birch_forest", true, 0.6, 0.6),
+        ("cherry_grove", true, 0.5, 0.8),
+        ("cold_ocean", true, 0.5, 0.5),
+        ("dark_forest", true, 0.7, 0.8),
+        ("deep_cold_ocean", true, 0.5, 0.5),
+        ("deep_dark", true, 0.8, 0.4),
+        ("deep_frozen_ocean", true, 0.5, 0.5),
+        ("deep_lukewarm_ocean", true, 0.5, 0.5),
+        ("deep_ocean", true, 0.5, 0.5),
+        ("desert", false, 2.0, 0.0),
+        ("dripstone_caves", true, 0.8, 0.4),
+        ("eroded_badlands", false, 2.0, 0.0),
+        ("flower_forest", true, 0.7, 0.8),
+        ("forest", true, 0.7, 0.8),
+        ("frozen_ocean", true, 0.0, 0.5),
+        ("frozen_peaks", true, -0.7, 0.9),
+        ("frozen_river", true, 0.0, 0.5),
+        ("grove", true, -0.2, 0.8),
+        ("ice_spikes", true, 0.0, 0.5),
+        ("jagged_peaks", true, -0.7, 0.9),
+        ("jungle", true, 0.95, 0.9),
+        ("lukewarm_ocean", true, 0.5, 0.5),
+        ("lush_caves", true, 0.5, 0.5),
+        ("mangrove_swamp", true, 0.8, 0.9),
+        ("meadow", true, 0.5, 0.8),
+        ("mushroom_fields", true, 0.9, 1.0),
+        ("ocean", true, 0.5, 0.5),
+        ("old_growth_birch_forest", true, 0.6, 0.6),
+        ("old_growth_pine_taiga", true, 0.3, 0.8),
+        ("old_growth_spruce_taiga", true, 0.25, 0.8),
+        ("river", true, 0.5, 0.5),
+        ("savanna", false, 2.0, 0.0),
+        ("savanna_plateau", false, 2.0, 0.0),
+        ("snowy_beach", true, 0.05, 0.3),
+        ("snowy_plains", true, 0.0, 0.5),
+        ("snowy_slopes", true, -0.3, 0.9),
+        ("snowy_taiga", true, -0.5, 0.4),
+        ("sparse_jungle", true, 0.95, 0.8),
+        ("stony_peaks", true, 1.0, 0.3),
+        ("stony_shore", true, 0.2, 0.3),
+        ("sunflower_plains", true, 0.8, 0.4),
+        ("swamp", true, 0.8, 0.9),
+        ("taiga", true, 0.25, 0.8),
+        ("the_void", false, 0.5, 0.5),
+        ("warm_ocean", true, 0.5, 0.5),
+        ("windswept_forest", true, 0.2, 0.3),
+        ("windswept_gravelly_hills", true, 0.2, 0.3),
+        ("windswept_hills", true, 0.2, 0.3),
+        ("windswept_savanna", false, 2.0, 0.0),
+        ("wooded_badlands", false, 2.0, 0.0)
+    ];
+    let mut biomes = Vec::new();
+    for (name, has_precipitation, temperature, downfall) in overworld {
+        let mut biome = overworld_biome(has_precipitation, temperature, downfall);
+        let effects = &mut biome.effects;
+        match name {
+            "badlands" | "eroded_badlands" | "wooded_badlands" => {
+                effects.foliage_color = Some(0x9e814d);
+                effects.grass_color = Some(0x90814d);
+            }
+            "cherry_grove" => {
+                (effects.water_color, effects.water_fog_color) = (0x5db7ef, 0x5db7ef);
+                (effects.foliage_color, effects.grass_color) = (Some(0xb6db61), Some(0xb6db61));
+            }
+            "cold_ocean" | "deep_cold_ocean" | "snowy_beach" | "snowy_taiga" => effects.water_color = 0x3d57d6,
+            "dark_forest" => effects.grass_color_modifier = Some("dark_forest".to_string()),
+            "frozen_ocean" | "deep_frozen_ocean" => {
+                effects.water_color = 0x3938c9;
+                biome.temperature_modifier = Some("frozen".to_string());
+            }
+            "frozen_river" => effects.water_color = 0x3938c9,
+            "lukewarm_ocean" | "deep_lukewarm_ocean" => (effects.water_color, effects.water_fog_color) = (0x45adf2, 0x041633),
+            "mangrove_swamp" => {
+                (effects.water_color, effects.water_fog_color) = (0x3a7a6a, 0x4d7a60);
+                effects.foliage_color = Some(0x8db127);
+                effects.grass_color_modifier = Some("swamp".to_string());
+            }
+            "meadow" => effects.water_color = 0x0e4ecf,
+            "swamp" => {
+                (effects.water_color, effects.water_fog_color) = (0x617b64, 0x232317);
+                effects.foliage_color = Some(0x6a7039);
+                effects.grass_color_modifier = Some("swamp".to_string());
+            }
+            "warm_ocean" => (effects.water_color, effects.water_fog_color) = (0x43d5ee, 0x041f33),
+            _ => {}
+        }
+        biomes.push((name, biome));
+    }
+    for (name, fog_color) in [
+        ("basalt_deltas", 0x685f70),
+        ("crimson_forest", 0x330303),
+        ("nether_wastes", 0x330808),
+        ("soul_sand_valley", 0x1b4745),
+        ("warped_forest", 0x1a051a)
+    ] {
+        biomes.push((name, nether_biome(name, fog_color)));
+    }
+    for name in ["end_barrens", "end_highlands", "end_midlands", "small_end_islands", "the_end"] {
+        biomes.push((name, end_biome()));
+    }
+    biomes
+}
+
+fn default_registries() -> Registries {
+    let chat = ChatDecoration {
+        translation_key: "chat.type.text".to_string(),
+        parameters: vec!["sender".to_string(), "content".to_string(), "target".to_string()],
+        style: None
+    };
+    let overworld = DimensionType {
+        fixed_time: None,
+        has_skylight: true,
+        has_ceiling: false,
+        ultrawarm: false,
+        natural: true,
+        coordinate_scale: 1.0,
+        bed_works: true,
+        respawn_anchor_works: false,
+        min_y: -64,
+        height: 384,
+        logical_height: 384,
+        infiniburn: "#minecraft:infiniburn_overworld".to_string(),
+        effects: "minecraft:overworld".to_string(),
+        ambient_light: 0.0,
+        piglin_safe: false,
+        has_raids: true,
+        monster_spawn_light_level: IntProvider::Ranged {
+            kind: "minecraft:uniform".to_string(),
+            value: IntRange { min_inclusive: 0, max_inclusive: 7 }
+        },
+        monster_spawn_block_light_limit: 0
+    };
+    let mut builder = RegistryBuilder::new();
+    for (name, biome) in vanilla_biomes() {
+        builder = builder.biome(minecraft(name), biome);
+    }
+    builder = builder
+        .chat_type(minecraft("default"), ChatType { chat: chat.clone(), narration: chat })
+        .dimension_type(minecraft("overworld"), overworld);
+
+    // the damage types the client looks up itself, with their vanilla message ids and exhaustion
+    let damage_types = [
+        ("arrow", "arrow", 0.1, None),
+        ("in_fire", "inFire", 0.1, Some("burning")),
+        ("lightning_bolt", "lightningBolt", 0.1, None),
+        ("on_fire", "onFire", 0.0, Some("burning")),
+        ("lava", "lava", 0.1, Some("burning")),
+        ("hot_floor", "hotFloor", 0.1, Some("burning")),
+        ("in_wall", "inWall", 0.0, None),
+        ("cramming", "cramming", 0.0, None),
+        ("drown", "drown", 0.0, Some("drowning")),
+        ("starve", "starve", 0.0, None),
+        ("cactus", "cactus", 0.1, None),
+        ("fall", "fall", 0.0, None),
+        ("fly_into_wall", "flyIntoWall", 0.0, None),
+        ("out_of_world", "outOfWorld", 0.0, None),
+        ("generic", "generic", 0.0, None),
+        ("magic", "magic", 0.0, None),
+        ("wither", "wither", 0.0, None),
+        ("dragon_breath", "dragonBreath", 0.0, None),
+        ("dry_out", "dryout", 0.1, None),
+        ("sweet_berry_bush", "sweetBerryBush", 0.1, Some("poking")),
+        ("freeze", "freeze", 0.0, Some("freezing")),
+        ("stalagmite", "stalagmite", 0.0, None),
+        ("outside_border", "outsideBorder", 0.0, None),
+        ("generic_kill", "genericKill", 0.0, None),
+        ("player_attack", "player", 0.1, None)
+    ];
+    for (name, message_id, exhaustion, effects) in damage_types {
+        builder = builder.damage_type(minecraft(name), DamageType {
+            message_id: message_id.to_string(),
+            scaling: DamageScaling::WhenCausedByLivingNonPlayer,
+            exhaustion,
+            effects: effects.map(str::to_string),
+            death_message_type: (name == "fall").then(|| "fall_variants".to_string())
+        });
+    }
+    builder.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn element(registries: &Registries, registry_id: &str, name: &str) -> HashMap<String, Value> {
+        let registry = registries.to_entries(ProtocolVersion::V1_20_6).unwrap()
+            .into_iter()
+            .find(|registry| registry.registry_id == registry_id)
+            .unwrap();
+        match registry.entries.into_iter().find(|entry| entry.id == name).and_then(|entry| entry.data) {
+            Some(Value::Compound(element)) => element,
+            other => panic!("unexpected {name}: {other:?}")
+        }
+    }
+
+    #[test]
+    fn default_registries_codec() {
+        let in_fire = element(&REGISTRY, DAMAGE_TYPE_REGISTRY, "minecraft:in_fire");
+        assert_eq!(in_fire.get("message_id"), Some(&Value::String("inFire".to_string())));
+        assert_eq!(in_fire.get("death_message_type"), None);
+        let overworld = element(&REGISTRY, DIMENSION_TYPE_REGISTRY, "minecraft:overworld");
+        assert_eq!(overworld.get("has_skylight"), Some(&Value::Byte(1)));
+        assert_eq!(overworld.get("min_y"), Some(&Value::Int(-64)));
+        assert_eq!(REGISTRY.dimension_types.id_of(&minecraft("overworld")), Some(0));
+        let Value::Compound(codec) = REGISTRY.to_codec(ProtocolVersion::V1_20_4).unwrap() else { unreachable!() };
+        assert!(!codec.contains_key(TRIM_MATERIAL_REGISTRY));
+
+        // the demo world's chunks use id 0, vanilla computes the sky from the temperature
+        assert_eq!(REGISTRY.biomes.id_of(&minecraft("plains")), Some(0));
+        assert_eq!(REGISTRY.biomes.len(), 64);
+        assert_eq!(REGISTRY.biomes.get(&minecraft("plains")).unwrap().effects.sky_color, 7907327);
+        assert_eq!(REGISTRY.biomes.get(&minecraft("desert")).unwrap().effects.sky_color, 7254527);
+        assert_eq!(REGISTRY.biomes.get(&minecraft("snowy_taiga")).unwrap().effects.sky_color, 8625919);
+        assert!(REGISTRY.biomes.get(&minecraft("the_void")).is_some());
+    }
+
+    #[test]
+    fn int_providers_follow_the_version() {
+        let range = |compound: &[(&str, Value)]| Some(Value::Compound(compound.iter().map(|(key, value)| (key.to_string(), value.clone())).collect()));
+        let light_level = |version| {
+            let Value::Compound(codec) = REGISTRY.to_codec(version).unwrap() else { unreachable!() };
+            let Some(Value::Compound(dimension_types)) = codec.get(DIMENSION_TYPE_REGISTRY) else { unreachable!() };
+            let Some(Value::List(entries)) = dimension_types.get("value") else { unreachable!() };
+            let Value::Compound(overworld) = &entries[0] else { unreachable!() };
+            let Some(Value::Compound(element)) = overworld.get("element") else { unreachable!() };
+            element.get("monster_spawn_light_level").cloned()
+        };
+        let kind = ("type", Value::String("minecraft:uniform".to_string()));
+        let bounds = [("min_inclusive", Value::Int(0)), ("max_inclusive", Value::Int(7))];
+        assert_eq!(light_level(ProtocolVersion::V1_20_4), range(&[kind.clone(), ("value", range(&bounds).unwrap())]));
+        assert_eq!(light_level(ProtocolVersion::V1_20_6), range(&[kind, bounds[0].clone(), bounds[1].clone()]));
+        let overworld = element(&REGISTRY, DIMENSION_TYPE_REGISTRY, "minecraft:overworld");
+        assert_eq!(overworld.get("monster_spawn_light_level"), light_level(ProtocolVersion::V1_20_6).as_ref());
+    }
+
+    // removes the datapack again even if an assertion fails
+    struct TempDir(PathBuf);
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn loads_datapacks() {
+        let pack = TempDir(std::env::temp_dir().join(format!("registry-datapack-{}", std::process::id())));
+        let write = |path: &str, json: &str| {
+            let path = pack.0.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, json).unwrap();
+        };
+        write("data/minecraft/damage_type/arrow.json", r#"{"message_id": "pierced", "scaling": "never", "exhaustion": 0.0}"#);
+        write("data/example/dimension_type/void/deep.json", r##"{
+            "fixed_time": 6000, "has_skylight": false, "has_ceiling": true, "ultrawarm": false, "natural": false,
+            "coordinate_scale": 8.0, "bed_works": false, "respawn_anchor_works": true, "min_y": 0, "height": 256,
+            "logical_height": 128, "infiniburn": "#minecraft:infiniburn_nether", "effects": "minecraft:the_nether",
+            "ambient_light": 0.1, "piglin_safe": true, "has_raids": false, "monster_spawn_block_light_limit": 15,
+            "monster_spawn_light_level": {"type": "minecraft:uniform", "min_inclusive": 7, "max_inclusive": 7}
+        }"##);
+        write("data/example/trim_material/glass.json", r#"{
+            "asset_name": "glass", "ingredient": "minecraft:glass", "item_model_index": 0.5,
+            "description": {"translate": "trim_material.example.glass", "color": "aqua"}
+        }"#);
+        write("data/example/wolf_variant/snowy.json", r#"{
+            "wild_texture": "minecraft:entity/wolf/wolf_snowy", "tame_texture": "minecraft:entity/wolf/wolf_snowy_tame",
+            "angry_texture": "minecraft:entity/wolf/wolf_snowy_angry", "biomes": "minecraft:grove"
+        }"#);
+        let registries = RegistryBuilder::from(REGISTRY.clone()).datapack(&pack.0).unwrap().build();
+
+        let arrow = registries.damage_types.get(&minecraft("arrow")).unwrap();
+        assert_eq!((arrow.message_id.as_str(), arrow.scaling), ("pierced", DamageScaling::Never));
+        assert_eq!(registries.damage_types.id_of(&minecraft("arrow")), Some(0));
+        let deep = Identifier::new("example", "void/deep").unwrap();
+        assert_eq!(registries.dimension_types.id_of(&deep), Some(1));
+        let glass = &registries.trim_materials.get(&Identifier::new("example", "glass").unwrap()).unwrap().description;
+        assert_eq!(glass, &TextComponent::translate("trim_material.example.glass").color("aqua"));
+        let glass = element(&registries, TRIM_MATERIAL_REGISTRY, "example:glass");
+        assert_eq!(glass.get("description"), Some(&Value::Compound(HashMap::from([
+            ("translate".to_string(), Value::String("trim_material.example.glass".to_string())),
+            ("color".to_string(), Value::String("aqua".to_string()))
+        ]))));
+        let Value::Compound(codec) = registries.to_codec(ProtocolVersion::V1_20_4).unwrap() else { unreachable!() };
+        assert!(!codec.contains_key(WOLF_VARIANT_REGISTRY));
+        element(&registries, WOLF_VARIANT_REGISTRY, "example:snowy");
+
+        write("data/example/damage_type/broken.json", r#"{"message_id": "broken"}"#);
+        let error = RegistryBuilder::new().datapack(&pack.0).unwrap_err().to_string();
+        assert!(error.contains("broken.json"), "{error}");
+    }
+}
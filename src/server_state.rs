@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use rand::seq::SliceRandom;
+use uuid::Uuid;
+
+use crate::status::{PlayerSample, StatusPlayers};
+use crate::text::TextComponent;
+
+// vanilla shows at most this many names when hovering the player count
+pub const MAX_SAMPLE_SIZE: usize = 12;
+
+// state shared by every connection, cloning gives another handle to the same players
+#[derive(Debug, Clone, Default)]
+pub struct ServerState {
+    players: Arc<Mutex<HashMap<Uuid, String>>>
+}
+
+impl ServerState {
+    pub fn new() -> Self {
+        ServerState::default()
+    }
+
+    // the player stays online until the returned handle is dropped
+    pub fn join(&self, id: Uuid, name: String) -> OnlinePlayer {
+        self.players.lock().unwrap().insert(id, name);
+        OnlinePlayer { id, state: self.clone() }
+    }
+
+    // checks and joins under one lock, so two players can't take the last slot or the same uuid at once
+    pub fn try_join(&self, id: Uuid, name: String, max_players: usize) -> Result<OnlinePlayer, JoinError> {
+        let mut players = self.players.lock().unwrap();
+        check_join(&players, id, max_players)?;
+        players.insert(id, name);
+        Ok(OnlinePlayer { id, state: self.clone() })
+    }
+
+    // lets a login be rejected early, the slot is only taken by `try_join`
+    pub fn check_join(&self, id: Uuid, max_players: usize) -> Result<(), JoinError> {
+        check_join(&self.players.lock().unwrap(), id, max_players)
+    }
+
+    pub fn online(&self) -> usize {
+        self.players.lock().unwrap().len()
+    }
+
+    pub fn is_online(&self, id: Uuid) -> bool {
+        self.players.lock().unwrap().contains_key(&id)
+    }
+
+    // up to MAX_SAMPLE_SIZE random players, in random order
+    pub fn sample(&self) -> Vec<PlayerSample> {
+        let players = self.players.lock().unwrap();
+        let players: Vec<_> = players.iter().collect();
+        players.choose_multiple(&mut rand::thread_rng(), MAX_SAMPLE_SIZE)
+            .map(|(id, name)| PlayerSample { name: name.to_string(), id: **id })
+            .collect()
+    }
+
+    // the count is always shown, hiding the player list only leaves out the names
+    pub fn status_players(&self, max: i32, hide_player_list: bool) -> StatusPlayers {
+        StatusPlayers {
+            max,
+            online: self.online() as i32,
+            sample: if hide_player_list { vec![] } else { self.sample() }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinError {
+    ServerFull,
+    AlreadyOnline
+}
+
+impl JoinError {
+    // vanilla's translation keys, so the client shows them in its own language
+    pub fn reason(self) -> TextComponent {
+        match self {
+            JoinError::ServerFull => TextComponent::translate("multiplayer.disconnect.server_full"),
+            JoinError::AlreadyOnline => TextComponent::translate("multiplayer.disconnect.duplicate_login")
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct OnlinePlayer {
+    id: Uuid,
+    state: ServerState
+}
+
+impl OnlinePlayer {
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+}
+
+impl Drop for OnlinePlayer {
+    fn drop(&mut self) {
+        self.state.players.lock().unwrap().remove(&self.id);
+    }
+}
+
+fn check_join(players: &HashMap<Uuid, String>, id: Uuid, max_players: usize) -> Result<(), JoinError> {
+    if players.contains_key(&id) {
+        return Err(JoinError::AlreadyOnline);
+    }
+    if players.len() >= max_players {
+        return Err(JoinError::ServerFull);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracks_and_samples_players() {
+        let state = ServerState::new();
+        let players: Vec<OnlinePlayer> = (0..20)
+            .map(|index| state.clone().join(Uuid::from_u128(index), format!("player{index}")))
+            .collect();
+        assert_eq!(state.online(), 20);
+
+        let status = state.status_players(100, false);
+        assert_eq!(status.online, 20);
+        assert_eq!(status.sample.len(), MAX_SAMPLE_SIZE);
+        assert!(status.sample.iter().all(|sample| sample.name == format!("player{}", sample.id.as_u128())));
+        assert!(state.status_players(100, true).sample.is_empty());
+
+        drop(players);
+        assert_eq!(state.online(), 0);
+        assert!(state.sample().is_empty());
+    }
+
+    #[test]
+    fn rejects_full_server_and_duplicates() {
+        let state = ServerState::new();
+        let first = state.try_join(Uuid::from_u128(1), "first".to_string(), 2).unwrap();
+        assert_eq!(state.try_join(Uuid::from_u128(1), "first".to_string(), 2).unwrap_err(), JoinError::AlreadyOnline);
+        let _second = state.try_join(Uuid::from_u128(2), "second".to_string(), 2).unwrap();
+        assert_eq!(state.check_join(Uuid::from_u128(3), 2), Err(JoinError::ServerFull));
+        drop(first);
+        assert_eq!(state.check_join(Uuid::from_u128(3), 2), Ok(()));
+        assert_eq!(state.online(), 1);
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::packets::login::Property;
+
+pub const MAX_USERNAME_LENGTH: usize = 16;
+pub const MOJANG_SESSION_SERVER: &str = "https://sessionserver.mojang.com";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameProfile {
+    pub id: Uuid,
+    pub name: String,
+    pub properties: Vec<Property>
+}
+
+// checks that a client has announced the join to the session service, like Mojang's hasJoined endpoint.
+// Ok(None) means the client isn't authenticated
+pub trait SessionVerifier: Send + Sync {
+    fn has_joined(&self, username: &str, server_hash: &str) -> impl Future<Output = Result<Option<GameProfile>>> + Send;
+}
+
+// in-memory stand-in for the session service, clients are authenticated by calling `join` with the server hash
+#[derive(Debug, Default)]
+pub struct LocalSessionVerifier {
+    joined: Mutex<HashMap<String, (String, GameProfile)>>
+}
+
+impl LocalSessionVerifier {
+    pub fn new() -> Self {
+        LocalSessionVerifier::default()
+    }
+
+    pub fn join(&self, server_hash: &str, profile: GameProfile) {
+        self.joined.lock().unwrap().insert(profile.name.clone(), (server_hash.to_string(), profile));
+    }
+}
+
+impl SessionVerifier for LocalSessionVerifier {
+    async fn has_joined(&self, username: &str, server_hash: &str) -> Result<Option<GameProfile>> {
+        let joined = self.joined.lock().unwrap();
+        Ok(joined.get(username)
+            .filter(|(joined_hash, _)| joined_hash == server_hash)
+            .map(|(_, profile)| profile.clone()))
+    }
+}
+
+// asks Mojang's session server, or another one with the same api, whether the client joined
+#[derive(Debug, Clone)]
+pub struct MojangSessionVerifier {
+    client: reqwest::Client,
+    base_url: String
+}
+
+// the profile json of hasJoined, the id is a uuid without dashes
+#[derive(Deserialize)]
+struct JoinedProfile {
+    id: Uuid,
+    name: String,
+    #[serde(default)]
+    properties: Vec<Property>
+}
+
+impl MojangSessionVerifier {
+    pub fn new() -> Self {
+        MojangSessionVerifier::with_base_url(MOJANG_SESSION_SERVER)
+    }
+
+    pub fn with_base_url(base_url: &str) -> Self {
+        MojangSessionVerifier { client: reqwest::Client::new(), base_url: base_url.trim_end_matches('/').to_string() }
+    }
+}
+
+impl Default for MojangSessionVerifier {
+    fn default() -> Self {
+        MojangSessionVerifier::new()
+    }
+}
+
+impl SessionVerifier for MojangSessionVerifier {
+    async fn has_joined(&self, username: &str, server_hash: &str) -> Result<Option<GameProfile>> {
+        let response = self.client.get(format!("{}/session/minecraft/hasJoined", self.base_url))
+            .query(&[("username", username), ("serverId", server_hash)])
+            .send()
+            .await?
+            .error_for_status()?;
+        // no content when the client didn't join
+        if response.status() == StatusCode::NO_CONTENT {
+            return Ok(None);
+        }
+        let profile: JoinedProfile = response.json().await?;
+        Ok(Some(GameProfile { id: profile.id, name: profile.name, properties: profile.properties }))
+    }
+}
+
+// vanilla only allows letters, digits and underscores in names
+pub fn is_valid_username(username: &str) -> bool {
+    (1..=MAX_USERNAME_LENGTH).contains(&username.len())
+        && username.bytes().all(|byte| byte.is_ascii_alphanumeric() || byte == b'_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn local_verifier_checks_server_hash() {
+        let verifier = LocalSessionVerifier::new();
+        let profile = GameProfile { id: Uuid::new_v4(), name: "Notch".to_string(), properties: vec![] };
+        verifier.join("-7c9d5b", profile.clone());
+        assert_eq!(verifier.has_joined("Notch", "-7c9d5b").await.unwrap(), Some(profile));
+        assert_eq!(verifier.has_joined("Notch", "4ed1f4").await.unwrap(), None);
+        assert_eq!(verifier.has_joined("jeb_", "-7c9d5b").await.unwrap(), None);
+    }
+
+    // answers a single request with `response` and returns the request line
+    async fn serve_once(listener: TcpListener, response: String) -> String {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut request = vec![0; 1024];
+        let read = socket.read(&mut request).await.unwrap();
+        socket.write_all(response.as_bytes()).await.unwrap();
+        String::from_utf8_lossy(&request[..read]).lines().next().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn mojang_verifier_reads_has_joined() {
+        let body = r#"{"id":"069a79f444e94726a5befca90e38aaf5","name":"Notch","properties":[{"name":"textures","value":"e30=","signature":"c2ln"}]}"#;
+        let response = format!("HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{body}", body.len());
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let verifier = MojangSessionVerifier::with_base_url(&format!("http://{}/", listener.local_addr().unwrap()));
+        let server = tokio::spawn(serve_once(listener, response));
+        let profile = verifier.has_joined("Notch", "-7c9d5b").await.unwrap().unwrap();
+        assert_eq!(server.await.unwrap(), "GET /session/minecraft/hasJoined?username=Notch&serverId=-7c9d5b HTTP/1.1");
+        assert_eq!(profile.id, Uuid::parse_str("069a79f4-44e9-4726-a5be-fca90e38aaf5").unwrap());
+        assert_eq!(profile.properties[0].signature.as_deref(), Some("c2ln"));
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let verifier = MojangSessionVerifier::with_base_url(&format!("http://{}", listener.local_addr().unwrap()));
+        tokio::spawn(serve_once(listener, "HTTP/1.1 204 No Content\r\n\r\n".to_string()));
+        assert_eq!(verifier.has_joined("Notch", "4ed1f4").await.unwrap(), None);
+    }
+
+    #[test]
+    fn username_validation() {
+        assert!(is_valid_username("Notch") && is_valid_username("jeb_") && is_valid_username("a234567890123456"));
+        assert!(!is_valid_username("") && !is_valid_username("a2345678901234567"));
+        assert!(!is_valid_username("two words") && !is_valid_username("ünicode") && !is_valid_username("§aName"));
+    }
+}
@@ -0,0 +1,99 @@
+use std::fmt::{Display, Formatter};
+
+use crate::version::ProtocolVersion;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConnectionState {
+    Handshaking,
+    Status,
+    Login,
+    Configuration,
+    Play
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StateError {
+    UnknownNextState(u32),
+    InvalidTransition { from: ConnectionState, to: ConnectionState }
+}
+
+impl ConnectionState {
+    // maps the `next_state` field of a handshake with the given protocol version.
+    // Since 1.20.5 a client transferred from another server sends 3 and then logs in as usual
+    pub fn from_next_state(next_state: u32, protocol: u32) -> Result<Self, StateError> {
+        match next_state {
+            1 => Ok(ConnectionState::Status),
+            2 => Ok(ConnectionState::Login),
+            3 if protocol >= ProtocolVersion::V1_20_6.protocol() => Ok(ConnectionState::Login),
+            other => Err(StateError::UnknownNextState(other))
+        }
+    }
+
+    pub fn can_transition_to(self, next: ConnectionState) -> bool {
+        use ConnectionState::*;
+        matches!(
+            (self, next),
+            (Handshaking, Status) | (Handshaking, Login) | (Login, Configuration) | (Configuration, Play) | (Play, Configuration)
+        )
+    }
+
+    pub fn transition(&mut self, next: ConnectionState) -> Result<(), StateError> {
+        if !self.can_transition_to(next) {
+            return Err(StateError::InvalidTransition { from: *self, to: next });
+        }
+        *self = next;
+        Ok(())
+    }
+}
+
+impl Display for ConnectionState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ConnectionState::Handshaking => "handshaking",
+            ConnectionState::Status => "status",
+            ConnectionState::Login => "login",
+            ConnectionState::Configuration => "configuration",
+            ConnectionState::Play => "play"
+        };
+        f.write_str(name)
+    }
+}
+
+impl Display for StateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StateError::UnknownNextState(next_state) => write!(f, "unknown next state {next_state} in handshake"),
+            StateError::InvalidTransition { from, to } => write!(f, "invalid state transition from {from} to {to}")
+        }
+    }
+}
+
+impl std::error::Error for StateError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn handshake_next_state() {
+        assert_eq!(ConnectionState::from_next_state(1, 765), Ok(ConnectionState::Status));
+        assert_eq!(ConnectionState::from_next_state(2, 765), Ok(ConnectionState::Login));
+        assert_eq!(ConnectionState::from_next_state(3, 766), Ok(ConnectionState::Login));
+        assert_eq!(ConnectionState::from_next_state(3, 765), Err(StateError::UnknownNextState(3)));
+        assert_eq!(ConnectionState::from_next_state(4, 766), Err(StateError::UnknownNextState(4)));
+    }
+
+    #[test]
+    fn transitions() {
+        let mut state = ConnectionState::Handshaking;
+        assert_eq!(
+            state.transition(ConnectionState::Play),
+            Err(StateError::InvalidTransition { from: ConnectionState::Handshaking, to: ConnectionState::Play })
+        );
+        assert_eq!(state, ConnectionState::Handshaking);
+        for next in [ConnectionState::Login, ConnectionState::Configuration, ConnectionState::Play, ConnectionState::Configuration] {
+            state.transition(next).unwrap();
+        }
+        assert!(!ConnectionState::Status.can_transition_to(ConnectionState::Login));
+    }
+}
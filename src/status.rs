@@ -0,0 +1,159 @@
+use std::path::Path;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::codec::MAX_STRING_LENGTH;
+use crate::error::{ProtocolError, Result};
+use crate::text::TextComponent;
+
+// the client only shows favicons of exactly this size
+pub const FAVICON_SIZE: u32 = 64;
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+// the json of Status Response, field names follow the vanilla format so responses of other servers parse too
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MinecraftStatus {
+    pub version: StatusVersion,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub players: Option<StatusPlayers>,
+    #[serde(default)]
+    pub description: TextComponent,
+    // data:image/png;base64,... url of a 64x64 png
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub favicon: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enforces_secure_chat: Option<bool>,
+    // removed in 1.19.3 but still sent by some servers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previews_chat: Option<bool>
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatusVersion {
+    pub name: String,
+    pub protocol: i32
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatusPlayers {
+    pub max: i32,
+    pub online: i32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sample: Vec<PlayerSample>
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerSample {
+    pub name: String,
+    pub id: Uuid
+}
+
+pub fn favicon_data_url(png_base64: &str) -> String {
+    format!("data:image/png;base64,{png_base64}")
+}
+
+// checks the IHDR header that has to follow the signature, the rest of the image is left to the client.
+// The data url has to fit in the string of Status Response
+pub fn favicon_from_png(png: &[u8]) -> Result<String> {
+    if png.len() < 24 || png[..8] != PNG_SIGNATURE || &png[12..16] != b"IHDR" {
+        return Err(ProtocolError::InvalidFavicon("not a png image".to_string()));
+    }
+    let width = u32::from_be_bytes(png[16..20].try_into().unwrap());
+    let height = u32::from_be_bytes(png[20..24].try_into().unwrap());
+    if width != FAVICON_SIZE || height != FAVICON_SIZE {
+        return Err(ProtocolError::InvalidFavicon(format!("image is {width}x{height}, expected {FAVICON_SIZE}x{FAVICON_SIZE}")));
+    }
+    let data_url = favicon_data_url(&STANDARD.encode(png));
+    if data_url.len() > MAX_STRING_LENGTH {
+        return Err(ProtocolError::InvalidFavicon(format!("image is {} bytes, too large for the status response", png.len())));
+    }
+    Ok(data_url)
+}
+
+pub fn load_favicon(path: impl AsRef<Path>) -> Result<String> {
+    favicon_from_png(&std::fs::read(path)?)
+}
+
+impl MinecraftStatus {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("status always serializes to json")
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|e| ProtocolError::InvalidStatus(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_and_round_trips() {
+        let status = MinecraftStatus {
+            version: StatusVersion { name: "1.20.4".to_string(), protocol: 765 },
+            players: Some(StatusPlayers {
+                max: 20,
+                online: 2,
+                sample: vec![
+                    PlayerSample { name: "Notch".to_string(), id: Uuid::nil() },
+                    PlayerSample { name: "quote\"back\\slash".to_string(), id: Uuid::from_u128(1) }
+                ]
+            }),
+            description: TextComponent::text("line one\nline \"two\""),
+            favicon: Some(favicon_data_url("iVBORw0KGgo=")),
+            enforces_secure_chat: Some(true),
+            previews_chat: None
+        };
+        let json = status.to_json();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed["players"]["sample"][1]["name"], "quote\"back\\slash");
+        assert_eq!(parsed["players"]["sample"][0]["id"], "00000000-0000-0000-0000-000000000000");
+        assert_eq!(parsed["enforcesSecureChat"], true);
+        assert!(parsed.get("previewsChat").is_none());
+        assert_eq!(MinecraftStatus::from_json(&json).unwrap(), status);
+    }
+
+    #[test]
+    fn parses_other_servers() {
+        let status = MinecraftStatus::from_json(r#"{
+            "version": {"name": "Paper 1.20.6", "protocol": 766},
+            "players": {"max": 100, "online": 0},
+            "description": "\u00a7aA Minecraft Server"
+        }"#).unwrap();
+        assert_eq!(status.version.protocol, 766);
+        assert_eq!(status.players.unwrap().sample, []);
+        assert_eq!(status.description, TextComponent::text("§aA Minecraft Server"));
+        assert_eq!(status.enforces_secure_chat, None);
+
+        let minimal = MinecraftStatus::from_json(r#"{"version": {"name": "1.20.2", "protocol": 764}}"#).unwrap();
+        assert_eq!(minimal.players, None);
+        assert!(MinecraftStatus::from_json(r#"{"players": {"max": 1, "online": 0}}"#).is_err());
+    }
+
+    #[test]
+    fn validates_favicons() {
+        let png = include_bytes!("../server-icon.png");
+        assert!(favicon_from_png(png).unwrap().starts_with("data:image/png;base64,iVBORw0KGgo"));
+
+        let mut large = png.to_vec();
+        large[16..24].copy_from_slice(&[0, 0, 0, 128, 0, 0, 0, 128]);
+        assert_eq!(favicon_from_png(&large).unwrap_err().to_string(), "invalid favicon: image is 128x128, expected 64x64");
+        assert!(favicon_from_png(b"GIF89a").is_err());
+        assert!(favicon_from_png(&png[..20]).is_err());
+
+        // a 64x64 png can still be too large for the status string, e.g. with lots of metadata
+        let mut padded = png.to_vec();
+        padded.resize(24558, 0);
+        assert_eq!(favicon_from_png(&padded).unwrap().len(), 32766);
+        padded.resize(24561, 0);
+        assert_eq!(
+            favicon_from_png(&padded).unwrap_err().to_string(),
+            "invalid favicon: image is 24561 bytes, too large for the status response"
+        );
+    }
+}
@@ -0,0 +1,467 @@
+use serde::{Deserialize, Deserializer, Serialize};
+use uuid::Uuid;
+
+use crate::codec::{ByteBuffer, MinecraftReadTypes, MinecraftWriteTypes};
+use crate::error::{ProtocolError, Result};
+use crate::nbt;
+use crate::version::ProtocolVersion;
+
+// chat component, sent as json in status, login and before 1.20.3, and as network nbt after that
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(from = "ComponentRepr")]
+pub struct TextComponent {
+    #[serde(flatten)]
+    pub content: Content,
+    // boxed, most components have no style and it would make every packet holding text large
+    #[serde(flatten)]
+    pub style: Box<Style>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra: Vec<TextComponent>
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Content {
+    Text {
+        text: String
+    },
+    Translatable {
+        translate: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        fallback: Option<String>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        with: Vec<TextComponent>
+    },
+    Score {
+        score: Score
+    },
+    Selector {
+        selector: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        separator: Option<Box<TextComponent>>
+    },
+    Keybind {
+        keybind: String
+    }
+}
+
+impl Default for Content {
+    fn default() -> Self {
+        Content::Text { text: String::new() }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Score {
+    // player name or selector
+    pub name: String,
+    pub objective: String
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Style {
+    // one of the named colors or #rrggbb
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "lenient_bool")]
+    pub bold: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "lenient_bool")]
+    pub italic: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "lenient_bool")]
+    pub underlined: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "lenient_bool")]
+    pub strikethrough: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "lenient_bool")]
+    pub obfuscated: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub font: Option<String>,
+    // text inserted into the chat box on shift click
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub insertion: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub click_event: Option<ClickEvent>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hover_event: Option<HoverEvent>
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", content = "value", rename_all = "snake_case")]
+pub enum ClickEvent {
+    OpenUrl(String),
+    RunCommand(String),
+    SuggestCommand(String),
+    ChangePage(String),
+    CopyToClipboard(String)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", content = "contents", rename_all = "snake_case")]
+pub enum HoverEvent {
+    ShowText(Box<TextComponent>),
+    ShowItem {
+        id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        count: Option<i32>,
+        // item nbt in snbt form
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        tag: Option<String>
+    },
+    ShowEntity {
+        #[serde(rename = "type")]
+        entity_type: String,
+        id: Uuid,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        name: Option<Box<TextComponent>>
+    }
+}
+
+// index is the code after the section sign in legacy text
+const NAMED_COLORS: [&str; 16] = [
+    "black", "dark_blue", "dark_green", "dark_aqua", "dark_red", "dark_purple", "gold", "gray",
+    "dark_gray", "blue", "green", "aqua", "red", "light_purple", "yellow", "white"
+];
+const LEGACY_CODES: &[u8; 16] = b"0123456789abcdef";
+
+// besides objects, vanilla accepts a plain string and a list whose tail is appended to the head
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ComponentRepr {
+    Text(String),
+    List(Vec<TextComponent>),
+    Object {
+        #[serde(flatten)]
+        content: Content,
+        #[serde(flatten)]
+        style: Box<Style>,
+        #[serde(default)]
+        extra: Vec<TextComponent>
+    }
+}
+
+impl From<ComponentRepr> for TextComponent {
+    fn from(repr: ComponentRepr) -> Self {
+        match repr {
+            ComponentRepr::Text(text) => TextComponent::text(text),
+            ComponentRepr::List(components) => {
+                let mut components = components.into_iter();
+                let head = components.next().unwrap_or_default();
+                components.fold(head, TextComponent::append)
+            }
+            ComponentRepr::Object { content, style, extra } => TextComponent { content, style, extra }
+        }
+    }
+}
+
+// nbt has no booleans, they arrive as bytes
+fn lenient_bool<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Option<bool>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Flag {
+        Bool(bool),
+        Byte(i8)
+    }
+    Ok(Option::<Flag>::deserialize(deserializer)?.map(|flag| match flag {
+        Flag::Bool(value) => value,
+        Flag::Byte(value) => value != 0
+    }))
+}
+
+impl TextComponent {
+    pub fn text(text: impl Into<String>) -> Self {
+        TextComponent { content: Content::Text { text: text.into() }, ..Default::default() }
+    }
+
+    pub fn translate(key: impl Into<String>) -> Self {
+        TextComponent::translate_with(key, Vec::new())
+    }
+
+    pub fn translate_with(key: impl Into<String>, with: Vec<TextComponent>) -> Self {
+        TextComponent { content: Content::Translatable { translate: key.into(), fallback: None, with }, ..Default::default() }
+    }
+
+    pub fn score(name: impl Into<String>, objective: impl Into<String>) -> Self {
+        TextComponent { content: Content::Score { score: Score { name: name.into(), objective: objective.into() } }, ..Default::default() }
+    }
+
+    pub fn selector(selector: impl Into<String>) -> Self {
+        TextComponent { content: Content::Selector { selector: selector.into(), separator: None }, ..Default::default() }
+    }
+
+    pub fn keybind(keybind: impl Into<String>) -> Self {
+        TextComponent { content: Content::Keybind { keybind: keybind.into() }, ..Default::default() }
+    }
+
+    pub fn color(mut self, color: impl Into<String>) -> Self {
+        self.style.color = Some(color.into());
+        self
+    }
+
+    pub fn bold(mut self, bold: bool) -> Self {
+        self.style.bold = Some(bold);
+        self
+    }
+
+    pub fn italic(mut self, italic: bool) -> Self {
+        self.style.italic = Some(italic);
+        self
+    }
+
+    pub fn underlined(mut self, underlined: bool) -> Self {
+        self.style.underlined = Some(underlined);
+        self
+    }
+
+    pub fn strikethrough(mut self, strikethrough: bool) -> Self {
+        self.style.strikethrough = Some(strikethrough);
+        self
+    }
+
+    pub fn obfuscated(mut self, obfuscated: bool) -> Self {
+        self.style.obfuscated = Some(obfuscated);
+        self
+    }
+
+    pub fn font(mut self, font: impl Into<String>) -> Self {
+        self.style.font = Some(font.into());
+        self
+    }
+
+    pub fn insertion(mut self, insertion: impl Into<String>) -> Self {
+        self.style.insertion = Some(insertion.into());
+        self
+    }
+
+    pub fn click(mut self, click_event: ClickEvent) -> Self {
+        self.style.click_event = Some(click_event);
+        self
+    }
+
+    pub fn hover(mut self, hover_event: HoverEvent) -> Self {
+        self.style.hover_event = Some(hover_event);
+        self
+    }
+
+    pub fn append(mut self, child: TextComponent) -> Self {
+        self.extra.push(child);
+        self
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("text components always serialize to json")
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|e| ProtocolError::InvalidText(e.to_string()))
+    }
+
+    pub fn write_json(&self, buffer: &mut ByteBuffer) {
+        buffer.write_var_string(&self.to_json());
+    }
+
+    pub fn read_json(buffer: &mut ByteBuffer) -> Result<Self> {
+        TextComponent::from_json(&buffer.read_var_string()?)
+    }
+
+    // the serde layout is shaped for json (flattened and untagged), so nbt goes through json values
+    pub fn write_nbt(&self, buffer: &mut ByteBuffer) -> Result<()> {
+        let json = serde_json::to_value(self).expect("text components always serialize to json");
+        match nbt::from_json(&json) {
+            Some(nbt) => nbt::write_tag(buffer, &nbt),
+            None => Ok(())
+        }
+    }
+
+    pub fn read_nbt(buffer: &mut ByteBuffer) -> Result<Self> {
+        match nbt::read_tag(buffer)? {
+            Some(nbt) => serde_json::from_value(nbt::to_json(&nbt)).map_err(|e| ProtocolError::InvalidText(e.to_string())),
+            None => Err(ProtocolError::InvalidText("missing root tag".to_string()))
+        }
+    }
+
+    // play and configuration packets switched from json to nbt in 1.20.3
+    pub fn write_for(&self, version: ProtocolVersion, buffer: &mut ByteBuffer) -> Result<()> {
+        if version >= ProtocolVersion::V1_20_4 {
+            self.write_nbt(buffer)
+        } else {
+            self.write_json(buffer);
+            Ok(())
+        }
+    }
+
+    pub fn read_for(version: ProtocolVersion, buffer: &mut ByteBuffer) -> Result<Self> {
+        if version >= ProtocolVersion::V1_20_4 {
+            TextComponent::read_nbt(buffer)
+        } else {
+            TextComponent::read_json(buffer)
+        }
+    }
+
+    // the text without any formatting, scores can't be resolved here and are left out
+    pub fn to_plain(&self) -> String {
+        let mut plain = String::new();
+        self.visit(&Style::default(), &mut |text, _| plain.push_str(text));
+        plain
+    }
+
+    // text with section sign codes as used before json components. Hex colors have no code and are dropped
+    pub fn to_legacy(&self) -> String {
+        let mut legacy = String::new();
+        let mut previous = String::new();
+        self.visit(&Style::default(), &mut |text, style| {
+            if text.is_empty() {
+                return;
+            }
+            let codes = legacy_codes(style);
+            if codes != previous {
+                // a color code resets the formatting on its own, otherwise it takes an explicit reset
+                if !legacy.is_empty() && !style.color.as_deref().is_some_and(is_named_color) {
+                    legacy.push_str("§r");
+                }
+                legacy.push_str(&codes);
+                previous = codes;
+            }
+            legacy.push_str(text);
+        });
+        legacy
+    }
+
+    // calls `visitor` with the text of each component and its style after inheriting from the parents
+    fn visit(&self, parent: &Style, visitor: &mut impl FnMut(&str, &Style)) {
+        let style = self.style.inherit(parent);
+        match &self.content {
+            Content::Text { text } => visitor(text, &style),
+            Content::Translatable { translate, fallback, .. } => visitor(fallback.as_ref().unwrap_or(translate), &style),
+            Content::Selector { selector, .. } => visitor(selector, &style),
+            Content::Keybind { keybind } => visitor(keybind, &style),
+            Content::Score { .. } => {}
+        }
+        for child in &self.extra {
+            child.visit(&style, visitor);
+        }
+    }
+}
+
+impl Style {
+    // unset fields take the parent's value, like children in `extra` do
+    fn inherit(&self, parent: &Style) -> Style {
+        Style {
+            color: self.color.clone().or_else(|| parent.color.clone()),
+            bold: self.bold.or(parent.bold),
+            italic: self.italic.or(parent.italic),
+            underlined: self.underlined.or(parent.underlined),
+            strikethrough: self.strikethrough.or(parent.strikethrough),
+            obfuscated: self.obfuscated.or(parent.obfuscated),
+            font: self.font.clone().or_else(|| parent.font.clone()),
+            insertion: self.insertion.clone().or_else(|| parent.insertion.clone()),
+            click_event: self.click_event.clone().or_else(|| parent.click_event.clone()),
+            hover_event: self.hover_event.clone().or_else(|| parent.hover_event.clone())
+        }
+    }
+}
+
+fn is_named_color(color: &str) -> bool {
+    NAMED_COLORS.contains(&color)
+}
+
+fn legacy_codes(style: &Style) -> String {
+    let mut codes = String::new();
+    if let Some(index) = style.color.as_deref().and_then(|color| NAMED_COLORS.iter().position(|named| *named == color)) {
+        codes.push('§');
+        codes.push(LEGACY_CODES[index] as char);
+    }
+    let formats = [(style.obfuscated, 'k'), (style.bold, 'l'), (style.strikethrough, 'm'), (style.underlined, 'n'), (style.italic, 'o')];
+    for (enabled, code) in formats {
+        if enabled == Some(true) {
+            codes.push('§');
+            codes.push(code);
+        }
+    }
+    codes
+}
+
+impl From<&str> for TextComponent {
+    fn from(text: &str) -> Self {
+        TextComponent::text(text)
+    }
+}
+
+impl From<String> for TextComponent {
+    fn from(text: String) -> Self {
+        TextComponent::text(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn styled() -> TextComponent {
+        TextComponent::text("Hello ")
+            .color("#ff8800")
+            .bold(true)
+            .click(ClickEvent::OpenUrl("https://example.com".to_string()))
+            .hover(HoverEvent::ShowText(Box::new(TextComponent::translate("chat.link.open"))))
+            .append(TextComponent::translate_with("multiplayer.player.joined", vec![TextComponent::selector("@p")]).italic(false))
+            .append(TextComponent::keybind("key.jump").underlined(true))
+            .append(TextComponent::score("@s", "kills"))
+            .append(TextComponent::text("?").hover(HoverEvent::ShowEntity {
+                entity_type: "minecraft:pig".to_string(),
+                id: Uuid::nil(),
+                name: None
+            }))
+    }
+
+    #[test]
+    fn json_layout() {
+        assert_eq!(TextComponent::text("description here").to_json(), r#"{"text":"description here"}"#);
+        assert_eq!(
+            TextComponent::text("a").color("red").bold(true).append("b".into()).to_json(),
+            r#"{"text":"a","color":"red","bold":true,"extra":[{"text":"b"}]}"#
+        );
+        assert_eq!(
+            TextComponent::text("").click(ClickEvent::RunCommand("/spawn".to_string())).to_json(),
+            r#"{"text":"","clickEvent":{"action":"run_command","value":"/spawn"}}"#
+        );
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let component = styled();
+        assert_eq!(TextComponent::from_json(&component.to_json()).unwrap(), component);
+        assert_eq!(TextComponent::from_json(r#""plain""#).unwrap(), TextComponent::text("plain"));
+        assert_eq!(
+            TextComponent::from_json(r#"["a", {"text": "b", "bold": true}]"#).unwrap(),
+            TextComponent::text("a").append(TextComponent::text("b").bold(true))
+        );
+        assert!(TextComponent::from_json(r#"{"bold": true}"#).is_err());
+    }
+
+    #[test]
+    fn nbt_round_trip() {
+        let component = styled();
+        for version in ProtocolVersion::SUPPORTED {
+            let mut buffer = ByteBuffer::new();
+            component.write_for(version, &mut buffer).unwrap();
+            assert_eq!(TextComponent::read_for(version, &mut buffer).unwrap(), component);
+            assert_eq!(buffer.readabe_bytes(), 0);
+        }
+        // booleans are bytes in nbt
+        let mut buffer = ByteBuffer::new();
+        TextComponent::text("a").bold(true).write_nbt(&mut buffer).unwrap();
+        assert_eq!(buffer.read_compound().unwrap(), fastnbt::nbt!({ "text": "a", "bold": 1i8 }));
+    }
+
+    #[test]
+    fn plain_and_legacy_text() {
+        let component = TextComponent::text("A ").color("gold")
+            .append(TextComponent::text("bold").bold(true))
+            .append(TextComponent::text(" server").color("#123456"))
+            .append(TextComponent::translate("missing.key").color("aqua"));
+        assert_eq!(component.to_plain(), "A bold servermissing.key");
+        assert_eq!(component.to_legacy(), "§6A §6§lbold§r server§bmissing.key");
+        assert_eq!(TextComponent::text("plain").to_legacy(), "plain");
+        // translation arguments and scores need the client, only the key is kept
+        assert_eq!(styled().to_plain(), "Hello multiplayer.player.joinedkey.jump?");
+    }
+}
@@ -0,0 +1,248 @@
+use std::fmt::{Display, Formatter};
+
+use fastnbt::Value;
+
+use crate::codec::{ByteBuffer, McDecode, McEncode, MinecraftReadTypes, MinecraftWriteTypes, MAX_STRING_LENGTH};
+use crate::error::{ProtocolError, Result};
+
+// block position, packed into a single long as x (26 bits), z (26 bits), y (12 bits)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Position {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32
+}
+
+impl Position {
+    pub fn new(x: i32, y: i32, z: i32) -> Self {
+        Position { x, y, z }
+    }
+
+    pub fn to_long(self) -> i64 {
+        ((self.x as i64 & 0x3ffffff) << 38) | ((self.z as i64 & 0x3ffffff) << 12) | (self.y as i64 & 0xfff)
+    }
+
+    pub fn from_long(value: i64) -> Self {
+        Position {
+            x: (value >> 38) as i32,
+            y: (value << 52 >> 52) as i32,
+            z: (value << 26 >> 38) as i32
+        }
+    }
+}
+
+// rotation in steps of 1/256 of a full turn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Angle(pub u8);
+
+impl Angle {
+    pub fn from_degrees(degrees: f32) -> Self {
+        Angle((degrees.rem_euclid(360.0) / 360.0 * 256.0).round() as u32 as u8)
+    }
+
+    pub fn degrees(self) -> f32 {
+        self.0 as f32 * 360.0 / 256.0
+    }
+}
+
+pub const DEFAULT_NAMESPACE: &str = "minecraft";
+
+// namespaced key like `minecraft:stone`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Identifier {
+    namespace: String,
+    path: String
+}
+
+impl Identifier {
+    pub fn new(namespace: &str, path: &str) -> Result<Self> {
+        let valid_namespace = namespace.chars().all(|c| matches!(c, 'a'..='z' | '0'..='9' | '.' | '-' | '_'));
+        let valid_path = path.chars().all(|c| matches!(c, 'a'..='z' | '0'..='9' | '.' | '-' | '_' | '/'));
+        if namespace.is_empty() || path.is_empty() || !valid_namespace || !valid_path {
+            return Err(ProtocolError::InvalidIdentifier(format!("{namespace}:{path}")));
+        }
+        Ok(Identifier { namespace: namespace.to_string(), path: path.to_string() })
+    }
+
+    // a missing or empty namespace defaults to `minecraft`, like vanilla reads `:stone`
+    pub fn parse(identifier: &str) -> Result<Self> {
+        if identifier.len() > MAX_STRING_LENGTH {
+            return Err(ProtocolError::StringTooLong { length: identifier.len(), max: MAX_STRING_LENGTH });
+        }
+        match identifier.split_once(':') {
+            Some(("", path)) => Identifier::new(DEFAULT_NAMESPACE, path),
+            Some((namespace, path)) => Identifier::new(namespace, path),
+            None => Identifier::new(DEFAULT_NAMESPACE, identifier)
+        }
+    }
+
+    pub fn minecraft(path: &str) -> Result<Self> {
+        Identifier::new(DEFAULT_NAMESPACE, path)
+    }
+
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+}
+
+impl Display for Identifier {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.namespace, self.path)
+    }
+}
+
+// bit set of any size, sent as a length-prefixed array of longs
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BitSet(pub Vec<u64>);
+
+impl BitSet {
+    pub fn new() -> Self {
+        BitSet::default()
+    }
+
+    pub fn get(&self, index: usize) -> bool {
+        self.0.get(index / 64).is_some_and(|word| word & (1 << (index % 64)) != 0)
+    }
+
+    pub fn set(&mut self, index: usize, value: bool) {
+        if index / 64 >= self.0.len() {
+            if !value {
+                return;
+            }
+            self.0.resize(index / 64 + 1, 0);
+        }
+        if value {
+            self.0[index / 64] |= 1 << (index % 64);
+        } else {
+            self.0[index / 64] &= !(1 << (index % 64));
+        }
+    }
+}
+
+// bit set with a size known from the packet, sent as ceil(size / 8) bytes without a length
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixedBitSet {
+    size: usize,
+    bytes: Vec<u8>
+}
+
+impl FixedBitSet {
+    pub fn new(size: usize) -> Self {
+        FixedBitSet { size, bytes: vec![0; size.div_ceil(8)] }
+    }
+
+    pub fn from_bytes(size: usize, bytes: Vec<u8>) -> Self {
+        let mut bytes = bytes;
+        bytes.resize(size.div_ceil(8), 0);
+        FixedBitSet { size, bytes }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn get(&self, index: usize) -> bool {
+        index < self.size && self.bytes[index / 8] & (1 << (index % 8)) != 0
+    }
+
+    pub fn set(&mut self, index: usize, value: bool) {
+        assert!(index < self.size, "bit {index} out of bounds for fixed bit set of size {}", self.size);
+        if value {
+            self.bytes[index / 8] |= 1 << (index % 8);
+        } else {
+            self.bytes[index / 8] &= !(1 << (index % 8));
+        }
+    }
+}
+
+// item stack in the pre 1.20.5 format, newer versions replaced the nbt with data components
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum Slot {
+    #[default]
+    Empty,
+    Item { item_id: i32, count: i8, nbt: Option<Value> }
+}
+
+macro_rules! impl_wire_type {
+    ($($ty:ty => $write:ident, $read:ident);* $(;)?) => {
+        $(
+            impl McEncode for $ty {
+                fn encode(&self, buffer: &mut ByteBuffer) -> Result<()> {
+                    buffer.$write(self);
+                    Ok(())
+                }
+            }
+
+            impl McDecode for $ty {
+                fn decode(buffer: &mut ByteBuffer) -> Result<Self> {
+                    buffer.$read()
+                }
+            }
+        )*
+    };
+}
+
+impl_wire_type! {
+    Position => write_position, read_position;
+    Identifier => write_identifier, read_identifier;
+    BitSet => write_bit_set, read_bit_set;
+}
+
+impl McEncode for Slot {
+    fn encode(&self, buffer: &mut ByteBuffer) -> Result<()> {
+        buffer.write_slot(self)
+    }
+}
+
+impl McDecode for Slot {
+    fn decode(buffer: &mut ByteBuffer) -> Result<Self> {
+        buffer.read_slot()
+    }
+}
+
+impl McEncode for Angle {
+    fn encode(&self, buffer: &mut ByteBuffer) -> Result<()> {
+        buffer.write_angle(*self);
+        Ok(())
+    }
+}
+
+impl McDecode for Angle {
+    fn decode(buffer: &mut ByteBuffer) -> Result<Self> {
+        buffer.read_angle()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bit_set_bits() {
+        let mut bit_set = BitSet::new();
+        bit_set.set(3, true);
+        bit_set.set(64, true);
+        assert!(bit_set.get(3) && bit_set.get(64) && !bit_set.get(4) && !bit_set.get(1000));
+        bit_set.set(64, false);
+        bit_set.set(1000, false);
+        assert_eq!(bit_set, BitSet(vec![8, 0]));
+    }
+
+    #[test]
+    fn identifier_namespaces() {
+        assert_eq!(Identifier::parse("minecraft:overworld").unwrap(), Identifier::minecraft("overworld").unwrap());
+        assert_eq!(Identifier::parse("velocity:player_info").unwrap().to_string(), "velocity:player_info");
+        assert!(Identifier::parse("a:b:c").is_err());
+        assert_eq!(Identifier::parse(":stone").unwrap(), Identifier::minecraft("stone").unwrap());
+        assert_eq!(Identifier::parse("stone").unwrap(), Identifier::minecraft("stone").unwrap());
+        assert!(Identifier::parse(":").is_err());
+    }
+}
@@ -0,0 +1,110 @@
+use std::fmt::{Display, Formatter};
+
+// protocol versions the packet id tables cover, in release order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub enum ProtocolVersion {
+    V1_20_2,
+    #[default]
+    V1_20_4,
+    V1_20_6
+}
+
+impl ProtocolVersion {
+    pub const SUPPORTED: [ProtocolVersion; 3] = [ProtocolVersion::V1_20_2, ProtocolVersion::V1_20_4, ProtocolVersion::V1_20_6];
+    pub const LATEST: ProtocolVersion = ProtocolVersion::V1_20_6;
+
+    // maps the `protocol_version` field of the handshake
+    pub fn from_protocol(protocol: u32) -> Option<Self> {
+        match protocol {
+            764 => Some(ProtocolVersion::V1_20_2),
+            765 => Some(ProtocolVersion::V1_20_4),
+            766 => Some(ProtocolVersion::V1_20_6),
+            _ => None
+        }
+    }
+
+    pub fn protocol(self) -> u32 {
+        match self {
+            ProtocolVersion::V1_20_2 => 764,
+            ProtocolVersion::V1_20_4 => 765,
+            ProtocolVersion::V1_20_6 => 766
+        }
+    }
+
+    // every release that shares the protocol number
+    pub fn name(self) -> &'static str {
+        match self {
+            ProtocolVersion::V1_20_2 => "1.20.2",
+            ProtocolVersion::V1_20_4 => "1.20.4",
+            ProtocolVersion::V1_20_6 => "1.20.6"
+        }
+    }
+
+    // serverbound play ids run from 0x00 (Confirm Teleportation) to Use Item
+    pub fn serverbound_play_packet_count(self) -> u32 {
+        match self {
+            ProtocolVersion::V1_20_2 => 0x36,
+            ProtocolVersion::V1_20_4 => 0x37,
+            ProtocolVersion::V1_20_6 => 0x3A
+        }
+    }
+}
+
+impl Display for ProtocolVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.name(), self.protocol())
+    }
+}
+
+// the id of one packet in each supported version, None where the version doesn't have it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PacketIds([Option<u32>; 3]);
+
+impl PacketIds {
+    pub const fn same(id: u32) -> Self {
+        PacketIds([Some(id); 3])
+    }
+
+    pub const fn new(v1_20_2: u32, v1_20_4: u32, v1_20_6: u32) -> Self {
+        PacketIds([Some(v1_20_2), Some(v1_20_4), Some(v1_20_6)])
+    }
+
+    // one entry per ProtocolVersion, used by the generated packets
+    pub const fn from_versions(ids: [Option<u32>; 3]) -> Self {
+        PacketIds(ids)
+    }
+
+    pub const fn until_1_20_4(id: u32) -> Self {
+        PacketIds([Some(id), Some(id), None])
+    }
+
+    pub const fn since_1_20_6(id: u32) -> Self {
+        PacketIds([None, None, Some(id)])
+    }
+
+    pub fn get(&self, version: ProtocolVersion) -> Option<u32> {
+        self.0[version as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn protocol_numbers() {
+        for version in ProtocolVersion::SUPPORTED {
+            assert_eq!(ProtocolVersion::from_protocol(version.protocol()), Some(version));
+        }
+        assert_eq!(ProtocolVersion::from_protocol(763), None);
+        assert!(ProtocolVersion::V1_20_2 < ProtocolVersion::LATEST);
+    }
+
+    #[test]
+    fn packet_ids() {
+        let ids = PacketIds::new(0x50, 0x52, 0x54);
+        assert_eq!(ids.get(ProtocolVersion::V1_20_2), Some(0x50));
+        assert_eq!(ids.get(ProtocolVersion::V1_20_6), Some(0x54));
+        assert_eq!(PacketIds::until_1_20_4(0x05).get(ProtocolVersion::V1_20_6), None);
+    }
+}
@@ -0,0 +1,30 @@
+use bytebuffer::ByteBuffer;
+use fastnbt::{nbt, Value};
+use once_cell::sync::Lazy;
+
+use crate::codec::MinecraftWriteTypes;
+
+pub static HEIGHT_MAP: Lazy<Value> = Lazy::new(|| {
+    nbt!({
+        "MOTION_BLOCKING": u64_filled_vec(36),
+        "WORLD_SURFACE": u64_filled_vec(36)
+    })
+});
+
+pub fn u64_filled_vec(size: usize) -> Vec<u64> {
+    vec![0; size]
+}
+
+// chunk section data where every section is air with a single-valued biome palette
+pub fn empty_chunk_sections(section_count: usize) -> Vec<u8> {
+    let mut buffer = ByteBuffer::new();
+    for _i in 0..section_count {
+        buffer.write_u16(0);
+        for _j in 0..2 {
+            buffer.write_var_int(0);
+            buffer.write_var_int(0);
+            buffer.write_var_int(0);
+        }
+    }
+    buffer.into_vec()
+}